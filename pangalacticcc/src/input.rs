@@ -0,0 +1,297 @@
+//! Reads the sentences of the input and of the files it includes.
+//! A line like `include "numerals.txt"` is replaced by the lines of numerals.txt,
+//! so shared definitions can be kept in one file.
+use crate::open;
+use crate::parser::{parse_sentence, Sentence, Statement};
+use crate::PccResult;
+use std::error;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// Occurs when a file can not be included, e.g. because it does not exist
+/// or because it is already being read
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncludeError {
+    /// path of the file to be included
+    path: PathBuf,
+    /// why the file can not be included
+    reason: String,
+    /// where the include was read, innermost first, e.g. "market.txt line 2"
+    chain: Vec<String>,
+}
+
+impl IncludeError {
+    /// Returns the path of the file to be included
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// Returns where the include was read, innermost first, e.g. "market.txt line 2"
+    pub fn chain(&self) -> &[String] {
+        &self.chain
+    }
+}
+
+impl error::Error for IncludeError {}
+
+impl Display for IncludeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Can not include {}: {}, included from {}",
+            self.path.display(),
+            self.reason,
+            self.chain.join(", from ")
+        )
+    }
+}
+
+/// A file or stdin being read
+struct Source {
+    /// path of the file, None for stdin
+    path: Option<PathBuf>,
+    /// path of the file with every link resolved, to notice when it is included again
+    canonical: Option<PathBuf>,
+    reader: Box<dyn BufRead>,
+    /// number of lines read so far
+    line: usize,
+}
+
+impl Source {
+    /// Returns where the source is at, e.g. "market.txt line 2"
+    fn position(&self) -> String {
+        match &self.path {
+            Some(path) => format!("{} line {}", path.display(), self.line),
+            None => format!("stdin line {}", self.line),
+        }
+    }
+}
+
+/// The sentences of the input, with the sentences of included files in place of the includes.
+/// Blank lines are left out.
+/// Included paths are relative to the including file, or to the working directory for stdin.
+/// An include that can not be read is returned as IncludeError and skipped,
+/// so reading can go on with the next sentence.
+pub struct Input {
+    /// the input and the files being included, the file read from is last
+    sources: Vec<Source>,
+}
+
+impl Input {
+    /// Opens the input at `path`, if `path` is `"-"` stdin is read, see open
+    pub fn open(path: &str) -> PccResult<Self> {
+        let reader = open(path)?;
+        if path == "-" {
+            return Ok(Input::from_reader(reader));
+        }
+        Ok(Input {
+            sources: vec![Source {
+                path: Some(PathBuf::from(path)),
+                canonical: std::fs::canonicalize(path).ok(),
+                reader,
+                line: 0,
+            }],
+        })
+    }
+
+    /// Reads the input from `reader`, included paths are relative to the working directory
+    /// # Example
+    /// ```
+    /// use pangalacticcc::input::Input;
+    /// use pangalacticcc::parser::Statement;
+    /// let text = "glob is I\n\nhow much is glob ?\n";
+    /// let sentences = Input::from_reader(text.as_bytes())
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(sentences.len(), 2);
+    /// assert!(matches!(sentences[1].statement(), Statement::HowMuch { .. }));
+    /// ```
+    pub fn from_reader<R: BufRead + 'static>(reader: R) -> Self {
+        Input {
+            sources: vec![Source {
+                path: None,
+                canonical: None,
+                reader: Box::new(reader),
+                line: 0,
+            }],
+        }
+    }
+
+    /// Continues reading with the file at `path`, relative to the file read from.
+    /// Fails if the file can not be opened or is already being read.
+    fn include(&mut self, path: &str) -> Result<(), IncludeError> {
+        let resolved = match self.sources.last().and_then(|s| s.path.as_deref()) {
+            Some(including) => including.parent().unwrap_or(Path::new("")).join(path),
+            None => PathBuf::from(path),
+        };
+        let opened = std::fs::canonicalize(&resolved)
+            .map_err(|e| e.to_string())
+            .and_then(|canonical| {
+                if self
+                    .sources
+                    .iter()
+                    .any(|s| s.canonical.as_ref() == Some(&canonical))
+                {
+                    return Err("it is already being read".to_string());
+                }
+                let file = File::open(&resolved).map_err(|e| e.to_string())?;
+                Ok((canonical, file))
+            });
+        match opened {
+            Ok((canonical, file)) => {
+                self.sources.push(Source {
+                    path: Some(resolved),
+                    canonical: Some(canonical),
+                    reader: Box::new(BufReader::new(file)),
+                    line: 0,
+                });
+                Ok(())
+            }
+            Err(reason) => Err(IncludeError {
+                path: resolved,
+                reason,
+                chain: self.include_chain(),
+            }),
+        }
+    }
+
+    /// Returns where the file read from was included, innermost first
+    fn include_chain(&self) -> Vec<String> {
+        self.sources.iter().rev().map(Source::position).collect()
+    }
+}
+
+impl Iterator for Input {
+    type Item = PccResult<Sentence>;
+
+    /// Returns the next sentence that is not blank or an include.
+    /// Fails with IncludeError if an included file can not be read, the rest of it is skipped
+    /// and reading goes on after the include. Fails with the I/O error if the input itself
+    /// can not be read, then there are no more sentences.
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = String::new();
+        loop {
+            let source = self.sources.last_mut()?;
+            line.clear();
+            match source.reader.read_line(&mut line) {
+                Ok(0) => {
+                    self.sources.pop();
+                }
+                Ok(_) => {
+                    source.line += 1;
+                    let s = parse_sentence(&line);
+                    match s.statement() {
+                        Statement::Include { path } => {
+                            if let Err(e) = self.include(path.text()) {
+                                return Some(Err(e.into()));
+                            }
+                        }
+                        // skip empty
+                        _ if line.trim().is_empty() => (),
+                        _ => return Some(Ok(s)),
+                    }
+                }
+                Err(e) if self.sources.len() == 1 => {
+                    self.sources.pop();
+                    return Some(Err(e.into()));
+                }
+                Err(e) => {
+                    let source = self.sources.pop()?;
+                    return Some(Err(IncludeError {
+                        path: source.path.unwrap_or_default(),
+                        reason: e.to_string(),
+                        chain: self.include_chain(),
+                    }
+                    .into()));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentences(input: Input) -> Vec<String> {
+        input
+            .map(|s| s.unwrap())
+            .filter(|s| s.statement() != &Statement::Comment)
+            .map(|s| s.text().to_string())
+            .collect::<Vec<_>>()
+    }
+
+    #[test]
+    fn test_include_relative_to_working_directory() {
+        let text = "include \"tests/input/include/numerals.txt\"\nhow much is pish glob ?\n";
+        let input = Input::from_reader(text.as_bytes());
+        assert_eq!(
+            sentences(input),
+            vec![
+                "glob is I",
+                "prok is V",
+                "pish is X",
+                "tegj is L",
+                "how much is pish glob ?"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_include_relative_to_including_file() {
+        let input = Input::open("tests/input/include/market.txt").unwrap();
+        assert_eq!(
+            sentences(input),
+            vec![
+                "glob glob Silver is 34 Credits",
+                "glob prok Gold is 57800 Credits",
+                "pish pish Iron is 3910 Credits"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_include_cycle() {
+        // cycle_a.txt includes cycle_b.txt in line 2, which includes cycle_a.txt again
+        let input = Input::open("tests/input/include/cycle_a.txt").unwrap();
+        let read = input.collect::<Vec<_>>();
+        assert_eq!(read.len(), 4);
+        let e = read[1].as_ref().unwrap_err();
+        let e = e.downcast_ref::<IncludeError>().unwrap();
+        assert_eq!(e.path(), Path::new("tests/input/include/cycle_a.txt"));
+        assert_eq!(
+            e.chain(),
+            vec![
+                "tests/input/include/cycle_b.txt line 1",
+                "tests/input/include/cycle_a.txt line 2"
+            ]
+        );
+        assert_eq!(
+            e.to_string(),
+            "Can not include tests/input/include/cycle_a.txt: it is already being read, \
+            included from tests/input/include/cycle_b.txt line 1, \
+            from tests/input/include/cycle_a.txt line 2"
+        );
+        // every file is read once, reading goes on after the error
+        let texts = read
+            .iter()
+            .filter_map(|s| s.as_ref().ok())
+            .map(|s| s.text())
+            .collect::<Vec<_>>();
+        assert_eq!(texts, vec!["glob is I", "prok is V", "pish is X"]);
+    }
+
+    #[test]
+    fn test_include_missing_file() {
+        let mut input = Input::from_reader("include \"missing.txt\"\nglob is I\n".as_bytes());
+        let e = input.next().unwrap().unwrap_err();
+        let e = e.downcast_ref::<IncludeError>().unwrap();
+        assert_eq!(e.chain(), vec!["stdin line 1"]);
+        assert!(e
+            .to_string()
+            .starts_with("Can not include missing.txt: No such file"));
+        assert_eq!(input.next().unwrap().unwrap().text(), "glob is I");
+        assert!(input.next().is_none());
+    }
+}
@@ -0,0 +1,493 @@
+//! What has been learned from the input so far:
+//! the symbols alien numerals stand for, the value of units and the exchange rates of currencies.
+use crate::numerals::NumeralSystem;
+use crate::parser::{Statement, Token, TokenKind};
+use crate::rational::{OverflowError, Rational};
+use crate::textprocessing::{
+    amount_symbol, arabic_amount, get_ignore_case, normalize_currency, normalize_whitespace,
+    split_amount_and_unit_tokens,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Value of one unit in a currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct Price {
+    value: Rational,
+    /// currency as written in the input
+    currency: String,
+}
+
+impl Price {
+    /// Creates a new price of `value` units of `currency`
+    pub fn new(value: Rational, currency: &str) -> Self {
+        Price {
+            value,
+            currency: currency.to_string(),
+        }
+    }
+    /// Returns the value in units of the currency
+    pub fn value(&self) -> Rational {
+        self.value
+    }
+    /// Returns the currency as written in the input
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+}
+
+/// Alien numerals, units and exchange rates learned from the input.
+/// Alien words are case-insensitive, they are stored in lowercase.
+/// Currencies are compared as returned by normalize_currency, Credits are always known.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnowledgeBase {
+    /// alien numerals -> symbols of the numeral system
+    numerals: HashMap<String, String>,
+    /// alien units -> value as exact fraction
+    units: HashMap<String, Price>,
+    /// (currency, target) -> value of one unit of currency in units of target,
+    /// stored in both directions
+    rates: HashMap<(String, String), Rational>,
+    /// currency in lowercase -> currency as first written
+    currencies: HashMap<String, String>,
+}
+
+impl Default for KnowledgeBase {
+    fn default() -> Self {
+        let mut currencies = HashMap::new();
+        currencies.insert("credits".to_string(), "Credits".to_string());
+        KnowledgeBase {
+            numerals: HashMap::new(),
+            units: HashMap::new(),
+            rates: HashMap::new(),
+            currencies,
+        }
+    }
+}
+
+impl KnowledgeBase {
+    /// Creates a knowledge base that only knows Credits
+    pub fn new() -> Self {
+        KnowledgeBase::default()
+    }
+
+    /// Returns the mapping of alien numerals to symbols
+    pub fn numerals(&self) -> &HashMap<String, String> {
+        &self.numerals
+    }
+
+    /// Returns the mapping of alien units to their price
+    pub fn units(&self) -> &HashMap<String, Price> {
+        &self.units
+    }
+
+    /// Learns that the alien numeral `numeral` stands for `symbol`
+    pub fn add_numeral(&mut self, numeral: &str, symbol: String) {
+        self.numerals.insert(numeral.to_lowercase(), symbol);
+    }
+
+    /// Forgets every alien numeral, e.g. because another numeral system was chosen
+    pub fn clear_numerals(&mut self) {
+        self.numerals.clear();
+    }
+
+    /// Remembers how a currency is written, see currency_name
+    fn add_currency(&mut self, currency: &str) {
+        self.currencies
+            .entry(currency.to_lowercase())
+            .or_insert_with(|| currency.to_string());
+    }
+
+    /// Learns that one `unit` is worth `price`.
+    /// Returns the previous price, if the unit was known before.
+    /// # Example
+    /// ```
+    /// use pangalacticcc::knowledge::{KnowledgeBase, Price};
+    /// use pangalacticcc::rational::Rational;
+    /// let mut kb = KnowledgeBase::new();
+    /// assert_eq!(kb.add_unit("Silver", Price::new(Rational::from(17), "Credits")), None);
+    /// assert_eq!(
+    ///     kb.add_unit("silver", Price::new(Rational::from(18), "Credits")),
+    ///     Some(Price::new(Rational::from(17), "Credits"))
+    /// );
+    /// ```
+    pub fn add_unit(&mut self, unit: &str, price: Price) -> Option<Price> {
+        self.add_currency(price.currency());
+        self.units.insert(unit.to_lowercase(), price)
+    }
+
+    /// Learns that one unit of `currency` is worth `value` units of `target`.
+    /// Returns the previous rate between the two currencies, if it was known before.
+    /// # Panics
+    /// Panics if `value` is zero.
+    pub fn add_exchange_rate(
+        &mut self,
+        currency: &str,
+        value: Rational,
+        target: &str,
+    ) -> Option<Rational> {
+        self.add_currency(currency);
+        self.add_currency(target);
+        let (from, to) = (normalize_currency(currency), normalize_currency(target));
+        self.rates
+            .insert((to.clone(), from.clone()), Rational::from(1) / value);
+        self.rates.insert((from, to), value)
+    }
+
+    /// Returns `value` units of `currency` converted to `target`, following the learned
+    /// exchange rates, or None if there is no way to convert.
+    /// Returns OverflowError if the result is too large to calculate with exactly.
+    /// # Example
+    /// ```
+    /// use pangalacticcc::knowledge::KnowledgeBase;
+    /// use pangalacticcc::rational::Rational;
+    /// let mut kb = KnowledgeBase::new();
+    /// kb.add_exchange_rate("Zorkmid", Rational::from(4), "Credits");
+    /// kb.add_exchange_rate("Quatloo", Rational::from(3), "Zorkmids");
+    /// assert_eq!(kb.convert(Rational::from(2), "Quatloos", "credits"), Some(Ok(Rational::from(24))));
+    /// assert_eq!(kb.convert(Rational::from(2), "Credits", "Zorkmids"), Some(Ok(Rational::new(1, 2))));
+    /// assert_eq!(kb.convert(Rational::from(2), "Credits", "Dollars"), None);
+    /// ```
+    pub fn convert(
+        &self,
+        value: Rational,
+        currency: &str,
+        target: &str,
+    ) -> Option<Result<Rational, OverflowError>> {
+        let target = normalize_currency(target);
+        let mut queue = VecDeque::from([(normalize_currency(currency), Ok(value))]);
+        let mut seen = HashSet::new();
+        // breadth first, so the shortest chain of exchange rates is used
+        while let Some((current, value)) = queue.pop_front() {
+            if current == target {
+                return Some(value);
+            }
+            if !seen.insert(current.clone()) {
+                continue;
+            }
+            for ((from, to), rate) in &self.rates {
+                if *from == current && !seen.contains(to) {
+                    let converted = value
+                        .clone()
+                        .and_then(|v| v.checked_mul(*rate).ok_or(OverflowError));
+                    queue.push_back((to.clone(), converted));
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns `value` units of `currency` converted to the number of `unit` they buy,
+    /// or None if the unit is not known, has no value or there is no way to convert.
+    /// Returns OverflowError if the result is too large to calculate with exactly.
+    /// # Example
+    /// ```
+    /// use pangalacticcc::knowledge::{KnowledgeBase, Price};
+    /// use pangalacticcc::rational::Rational;
+    /// let mut kb = KnowledgeBase::new();
+    /// kb.add_unit("Silver", Price::new(Rational::from(17), "Credits"));
+    /// assert_eq!(kb.convert_to_unit(Rational::from(51), "Credits", "silver"), Some(Ok(Rational::from(3))));
+    /// assert_eq!(kb.convert_to_unit(Rational::from(51), "Credits", "Gold"), None);
+    /// ```
+    pub fn convert_to_unit(
+        &self,
+        value: Rational,
+        currency: &str,
+        unit: &str,
+    ) -> Option<Result<Rational, OverflowError>> {
+        let price = get_ignore_case(&self.units, unit)?;
+        if price.value() == Rational::from(0) {
+            return None;
+        }
+        let converted = self.convert(value, currency, price.currency())?;
+        Some(converted.and_then(|v| v.checked_div(price.value()).ok_or(OverflowError)))
+    }
+
+    /// Returns true if `unit` is a known unit that is worth nothing, e.g. "glob Dirt is 0 Credits",
+    /// so nothing can be converted to it
+    /// # Example
+    /// ```
+    /// use pangalacticcc::knowledge::{KnowledgeBase, Price};
+    /// use pangalacticcc::rational::Rational;
+    /// let mut kb = KnowledgeBase::new();
+    /// kb.add_unit("Dirt", Price::new(Rational::from(0), "Credits"));
+    /// kb.add_unit("Silver", Price::new(Rational::from(17), "Credits"));
+    /// assert!(kb.is_worthless("dirt"));
+    /// assert!(!kb.is_worthless("Silver"));
+    /// assert!(!kb.is_worthless("Gold"));
+    /// ```
+    pub fn is_worthless(&self, unit: &str) -> bool {
+        get_ignore_case(&self.units, unit).is_some_and(|price| price.value() == Rational::from(0))
+    }
+
+    /// Returns true if `target` of a "how many" question is a known unit, otherwise it is a currency
+    pub fn is_unit(&self, target: &str) -> bool {
+        get_ignore_case(&self.units, target).is_some()
+    }
+
+    /// Returns true if `currency` has been used in a statement, Credits are always known
+    /// # Example
+    /// ```
+    /// use pangalacticcc::knowledge::KnowledgeBase;
+    /// use pangalacticcc::rational::Rational;
+    /// let mut kb = KnowledgeBase::new();
+    /// kb.add_exchange_rate("Zorkmid", Rational::from(4), "Credits");
+    /// assert!(kb.is_currency("credit"));
+    /// assert!(kb.is_currency("Zorkmids"));
+    /// assert!(!kb.is_currency("Copper"));
+    /// ```
+    pub fn is_currency(&self, currency: &str) -> bool {
+        let currency = normalize_currency(currency);
+        self.currencies
+            .keys()
+            .any(|known| normalize_currency(known) == currency)
+    }
+
+    /// Returns how to write `currency` in answers: as it was first written in the input
+    /// if it only differs in case, otherwise as given.
+    /// # Example
+    /// ```
+    /// use pangalacticcc::knowledge::KnowledgeBase;
+    /// let kb = KnowledgeBase::new();
+    /// assert_eq!(kb.currency_name("credits"), "Credits");
+    /// assert_eq!(kb.currency_name("Credit"), "Credit");
+    /// ```
+    pub fn currency_name(&self, currency: &str) -> String {
+        self.currencies
+            .get(&currency.to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| currency.to_string())
+    }
+
+    /// Splits the words of an amount followed by a unit into alien numerals and the unit.
+    /// Without quotes the longest known unit at the end is preferred,
+    /// so that unknown words before it are reported as numerals.
+    /// An amount in arabic numerals is the first word, followed by the unit,
+    /// otherwise see split_amount_and_unit_tokens. If that fails, the last word is the unit.
+    /// Returns None if there are no words.
+    pub fn split_amount_and_unit<'a>(&self, words: &'a [Token]) -> Option<(&'a [Token], String)> {
+        let quoted = words.iter().any(|t| t.kind() == TokenKind::Quoted);
+        let known =
+            (0..words.len()).find(|i| get_ignore_case(&self.units, &join(&words[*i..])).is_some());
+        match (quoted, known) {
+            (false, Some(i)) => Some((&words[..i], join(&words[i..]))),
+            (false, None)
+                if words.len() > 1
+                    && arabic_amount(&self.numerals, &[words[0].text()]).is_some() =>
+            {
+                Some((&words[..1], join(&words[1..])))
+            }
+            _ => split_amount_and_unit_tokens(&self.numerals, words).or_else(|| {
+                words
+                    .split_last()
+                    .map(|(unit, amount)| (amount, unit.text().to_string()))
+            }),
+        }
+    }
+
+    /// Returns true if the amount `words` can be read: every alien numeral is known,
+    /// or the amount is written in arabic numerals or as symbols of `system` directly
+    fn knows_numerals<N: NumeralSystem + ?Sized>(&self, system: &N, words: &[Token]) -> bool {
+        let words = words.iter().map(|t| t.text()).collect::<Vec<_>>();
+        arabic_amount(&self.numerals, &words).is_some()
+            || words
+                .iter()
+                .all(|word| amount_symbol(system, &self.numerals, word).is_some())
+    }
+
+    /// Returns true if everything a question depends on is known, i.e. it can be answered
+    /// without waiting for further definitions. Statements other than questions never wait.
+    /// Saying a number needs alien numerals for all the symbols of `system` it is written with.
+    /// # Example
+    /// ```
+    /// use pangalacticcc::knowledge::{KnowledgeBase, Price};
+    /// use pangalacticcc::numerals::RomanNumerals;
+    /// use pangalacticcc::parser::parse_sentence;
+    /// use pangalacticcc::rational::Rational;
+    /// let roman = RomanNumerals::default();
+    /// let mut kb = KnowledgeBase::new();
+    /// let question = parse_sentence("how many Zorkmids is glob Dark Matter ?");
+    /// assert!(!kb.can_answer(&roman, question.statement()));
+    /// kb.add_numeral("glob", "I".to_string());
+    /// kb.add_unit("Dark Matter", Price::new(Rational::from(17), "Credits"));
+    /// assert!(!kb.can_answer(&roman, question.statement()));
+    /// kb.add_exchange_rate("Zorkmid", Rational::from(4), "Credits");
+    /// assert!(kb.can_answer(&roman, question.statement()));
+    /// ```
+    pub fn can_answer<N: NumeralSystem + ?Sized>(&self, system: &N, statement: &Statement) -> bool {
+        match statement {
+            Statement::HowMuch { amount } => self.knows_numerals(system, amount),
+            Statement::HowMany { target, words } => match self.split_amount_and_unit(words) {
+                Some((amount, unit)) => {
+                    let target = normalize_whitespace(&join(target));
+                    self.knows_numerals(system, amount)
+                        && get_ignore_case(&self.units, &unit).is_some_and(|price| {
+                            self.can_convert(price.value(), price.currency(), &target)
+                        })
+                }
+                None => true,
+            },
+            // an amount of a unit in arabic numerals, e.g. "how many Credits is 3 Silver ?"
+            Statement::Purchase {
+                target, currency, ..
+            } if self.is_unit(currency.text()) => get_ignore_case(&self.units, currency.text())
+                .is_some_and(|price| {
+                    self.can_convert(
+                        price.value(),
+                        price.currency(),
+                        &normalize_whitespace(&join(target)),
+                    )
+                }),
+            Statement::Purchase {
+                target, currency, ..
+            } => self.can_convert(
+                Rational::from(1),
+                currency.text(),
+                &normalize_whitespace(&join(target)),
+            ),
+            Statement::HowToSay { number } => match number.text().parse::<i128>() {
+                Ok(number) => system
+                    .render(Rational::from(number))
+                    .map_or(true, |symbols| {
+                        symbols
+                            .iter()
+                            .all(|symbol| self.numerals.values().any(|s| s == symbol))
+                    }),
+                Err(_) => true,
+            },
+            _ => true,
+        }
+    }
+
+    /// Returns true if `value` in `currency` can be converted to `target`, a unit or a currency
+    fn can_convert(&self, value: Rational, currency: &str, target: &str) -> bool {
+        if self.is_unit(target) {
+            self.convert_to_unit(value, currency, target).is_some()
+        } else {
+            self.convert(value, currency, target).is_some()
+        }
+    }
+}
+
+/// Returns the texts of `tokens` separated by single spaces
+fn join(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|t| t.text())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::numerals::RomanNumerals;
+    use crate::parser::parse_sentence;
+
+    #[test]
+    fn test_knowledge_base_ignores_case() {
+        let mut kb = KnowledgeBase::new();
+        kb.add_numeral("Glob", "I".to_string());
+        assert_eq!(kb.numerals().get("glob"), Some(&"I".to_string()));
+        kb.add_unit("Silver", Price::new(Rational::from(17), "Credits"));
+        assert_eq!(
+            kb.units().get("silver").unwrap().value(),
+            Rational::from(17)
+        );
+    }
+
+    #[test]
+    fn test_can_answer_how_much() {
+        let roman = RomanNumerals::default();
+        let mut kb = KnowledgeBase::new();
+        let question = parse_sentence("how much is glob PROK ?");
+        kb.add_numeral("glob", "I".to_string());
+        assert!(!kb.can_answer(&roman, question.statement()));
+        kb.add_numeral("prok", "V".to_string());
+        assert!(kb.can_answer(&roman, question.statement()));
+        kb.clear_numerals();
+        assert!(!kb.can_answer(&roman, question.statement()));
+    }
+
+    #[test]
+    fn test_can_answer_quoted_unit() {
+        let roman = RomanNumerals::default();
+        let mut kb = KnowledgeBase::new();
+        kb.add_numeral("glob", "I".to_string());
+        let question = parse_sentence("how many Credits is glob \"glob Dust\" ?");
+        assert!(!kb.can_answer(&roman, question.statement()));
+        kb.add_unit("glob Dust", Price::new(Rational::from(3), "Credits"));
+        assert!(kb.can_answer(&roman, question.statement()));
+    }
+
+    #[test]
+    fn test_can_answer_anything_else() {
+        let roman = RomanNumerals::default();
+        let kb = KnowledgeBase::new();
+        for sentence in ["glob is I", "how much wood ?", "how many Credits is ?"] {
+            assert!(kb.can_answer(&roman, parse_sentence(sentence).statement()));
+        }
+    }
+
+    #[test]
+    fn test_convert_same_currency() {
+        let kb = KnowledgeBase::new();
+        assert_eq!(
+            kb.convert(Rational::from(3), "Credits", "credit"),
+            Some(Ok(Rational::from(3)))
+        );
+    }
+
+    #[test]
+    fn test_exchange_rate_redefined() {
+        let mut kb = KnowledgeBase::new();
+        assert_eq!(
+            kb.add_exchange_rate("Zorkmid", Rational::from(4), "Credits"),
+            None
+        );
+        assert_eq!(
+            kb.add_exchange_rate("Credits", Rational::new(1, 5), "Zorkmids"),
+            Some(Rational::new(1, 4))
+        );
+        assert_eq!(
+            kb.convert(Rational::from(1), "Zorkmid", "Credits"),
+            Some(Ok(Rational::from(5)))
+        );
+    }
+
+    #[test]
+    fn test_can_answer_unit_target() {
+        let roman = RomanNumerals::default();
+        let mut kb = KnowledgeBase::new();
+        kb.add_numeral("glob", "I".to_string());
+        kb.add_unit("Gold", Price::new(Rational::from(3), "Zorkmids"));
+        let question = parse_sentence("how many Silver is glob Gold ?");
+        assert!(!kb.can_answer(&roman, question.statement()));
+        kb.add_unit("Silver", Price::new(Rational::from(17), "Credits"));
+        assert!(!kb.can_answer(&roman, question.statement()));
+        kb.add_exchange_rate("Zorkmid", Rational::from(4), "Credits");
+        assert!(kb.can_answer(&roman, question.statement()));
+    }
+
+    #[test]
+    fn test_convert_to_worthless_unit() {
+        let mut kb = KnowledgeBase::new();
+        kb.add_unit("Dirt", Price::new(Rational::from(0), "Credits"));
+        assert_eq!(
+            kb.convert_to_unit(Rational::from(3), "Credits", "Dirt"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_can_answer_literal_amounts() {
+        let roman = RomanNumerals::default();
+        let mut kb = KnowledgeBase::new();
+        for question in ["how much is XLII ?", "how much is 42 ?"] {
+            assert!(kb.can_answer(&roman, parse_sentence(question).statement()));
+        }
+        let question = parse_sentence("how many Credits is 3 Silver ?");
+        assert!(!kb.can_answer(&roman, question.statement()));
+        kb.add_unit("Silver", Price::new(Rational::from(17), "Credits"));
+        assert!(kb.can_answer(&roman, question.statement()));
+    }
+}
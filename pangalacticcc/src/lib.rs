@@ -4,48 +4,52 @@
 //! Output is printed to stdout.
 extern crate core;
 
+pub mod input;
+pub mod knowledge;
+pub mod numerals;
+pub mod parser;
+pub mod rational;
 pub mod roman;
 pub mod textprocessing;
 
-use crate::roman::Roman;
+use crate::input::{IncludeError, Input};
+use crate::knowledge::{KnowledgeBase, Price};
+use crate::numerals::{numeral_system_from_name, NumeralError, NumeralSystem, RomanNumerals};
+use crate::parser::{parse_sentence, Sentence, Statement, Token};
+use crate::rational::{OverflowError, Rational};
+use crate::roman::{ParseMode, Style};
 use crate::textprocessing::{
-    extract_unit_values_from_sentence, is_numeral_info, is_question_how_many_credits,
-    is_question_how_much, is_unit_info, numerals_to_roman,
+    amount_symbol, arabic_amount, extract_exchange_rate, extract_unit_value, get_ignore_case,
+    normalize_currency, normalize_whitespace, ZeroAmountError, ZeroExchangeRateError,
 };
 
-use crate::StatementKind::{
-    HowManyQuestion, HowMuchQuestion, NumeralStatement, Uncategorized, UnitStatement,
-};
 use clap::{Arg, Command};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::error::Error;
 use std::fs::File;
-use std::io::{BufRead, Read};
+use std::io::BufRead;
 
 type PccResult<T> = Result<T, Box<dyn Error>>;
 
 const DEFAULT_RESPONSE: &str = "I have no idea what you are talking about";
 
-/// Holds the path of the file to be processed as String.
+/// Number of questions run_stream keeps waiting for definitions,
+/// if another question has to wait the oldest one is answered with what is known
+pub const MAX_WAITING: usize = 1000;
+
+/// Holds the path of the file to be processed as String
+/// and the options chosen on the command line.
 #[derive(Debug)]
 pub struct Config {
     /// path to the input file with the gathered information.
     /// If path is "-", input will be read from stdin.
     path: String,
-}
-#[derive(Debug, PartialEq)]
-enum StatementKind {
-    HowMuchQuestion,
-    HowManyQuestion,
-    UnitStatement,
-    NumeralStatement,
-    Uncategorized,
-}
-
-#[derive(Debug)]
-struct InputStatement {
-    kind: StatementKind,
-    text: String,
+    /// how strictly Roman numerals are checked
+    mode: ParseMode,
+    /// glyphs used when showing Roman numerals
+    style: Style,
+    /// read the input line by line instead of reading all of it first
+    stream: bool,
 }
 
 /// Parses command line arguments
@@ -62,11 +66,54 @@ pub fn get_args() -> PccResult<Config> {
                     If set to '-' or no FILE is specified, input is read from stdin.",
                 ),
         )
+        .arg(
+            Arg::new("lenient")
+                .short('l')
+                .long("lenient")
+                .takes_value(false)
+                .help(
+                    "Accept non-canonical numerals such as IIII or IIX \
+                    when answering \"how much is ...\" questions.",
+                ),
+        )
+        .arg(
+            Arg::new("style")
+                .short('s')
+                .long("style")
+                .takes_value(true)
+                .value_name("STYLE")
+                .possible_values(["ascii", "lowercase", "unicode", "unicode-lowercase"])
+                .default_value("ascii")
+                .help("Glyphs used when showing Roman numerals."),
+        )
+        .arg(Arg::new("stream").long("stream").takes_value(false).help(
+            "Read the input line by line and answer questions as soon as \
+                    the alien numerals and units they ask about are known. \
+                    Keeps memory low for very large inputs.",
+        ))
         .get_matches();
 
     // we can safely unwrap here because we set a default
     let path = matches.value_of_lossy("input_path").unwrap().to_string();
-    Ok(Config { path })
+    let mode = if matches.is_present("lenient") {
+        ParseMode::Lenient
+    } else {
+        ParseMode::Strict
+    };
+    // possible values are checked by clap
+    let style = match matches.value_of("style") {
+        Some("lowercase") => Style::Lowercase,
+        Some("unicode") => Style::Unicode,
+        Some("unicode-lowercase") => Style::UnicodeLowercase,
+        _ => Style::Ascii,
+    };
+    let stream = matches.is_present("stream");
+    Ok(Config {
+        path,
+        mode,
+        style,
+        stream,
+    })
 }
 
 /// Runs the program on provided config.
@@ -82,223 +129,843 @@ pub fn run(config: Config) -> PccResult<()> {
     //   -> answering questions "how many Credits is $amount $unit ?" possible [x]
     // check for invalid inputs
 
-    let mut reader = open(&config.path)?;
-    let mut buff = String::new();
+    // included files are read in place of the includes
+    let input = Input::open(&config.path)?;
+    // the input may choose a numeral system, roman numerals are used by default
+    let roman = RomanNumerals::new(config.mode).with_style(config.style);
+    if config.stream {
+        return run_stream(roman, input);
+    }
 
-    // this assumes the input is of manageable size
-    reader.read_to_string(&mut buff)?;
+    // this assumes the input is of manageable size, see run_stream otherwise
+    let mut sentences = Vec::new();
+    for read in input {
+        sentences.extend(included(read)?);
+    }
 
-    // strip whitespace from start end end of sentences
-    let contents = buff
-        .split('\n')
-        .into_iter()
-        .map(|x| x.trim_end().trim_start())
-        .collect::<Vec<_>>();
+    let mut system: Box<dyn NumeralSystem> = Box::new(roman);
+    for name in sentences.iter().filter_map(|x| match x.statement() {
+        Statement::NumeralSystem { name } => Some(join_tokens(name)),
+        _ => None,
+    }) {
+        system = choose_numeral_system(&name, roman, system);
+    }
+    run_with(&*system, &sentences);
+    Ok(())
+}
 
-    let mut statements: Vec<InputStatement> = Vec::new();
-    for statement in &contents {
-        // skip empty
-        if statement.is_empty() {
-            continue;
+/// Returns a sentence read from the input, or None after printing why a file could not be
+/// included. Fails if the input itself can not be read.
+fn included(read: PccResult<Sentence>) -> PccResult<Option<Sentence>> {
+    match read {
+        Ok(s) => Ok(Some(s)),
+        Err(e) if e.is::<IncludeError>() => {
+            println!("{}", e);
+            Ok(None)
         }
-        if is_numeral_info(statement) {
-            // collect statements about alien numerals
-            statements.push(InputStatement {
-                text: statement.to_string(),
-                kind: NumeralStatement,
-            });
-        } else if is_unit_info(statement) {
-            // collect statements about alien units
-            statements.push(InputStatement {
-                text: statement.to_string(),
-                kind: UnitStatement,
-            });
-        } else if is_question_how_many_credits(statement) {
-            // collect questions how many credits is <sequence_of_alien_numerals> <alien_unit>
-            statements.push(InputStatement {
-                text: statement.to_string(),
-                kind: HowManyQuestion,
-            })
-        } else if is_question_how_much(statement) {
-            // collect questions how much is <sequence_of_alien_numerals>
-            statements.push(InputStatement {
-                text: statement.to_string(),
-                kind: HowMuchQuestion,
-            })
-        } else {
-            // collect anything else that doesn't match the other patterns
-            statements.push(InputStatement {
-                text: statement.to_string(),
-                kind: Uncategorized,
-            })
+        Err(e) => Err(e),
+    }
+}
+
+/// Returns the numeral system called `name`, or `current` if there is no such system
+fn choose_numeral_system(
+    name: &str,
+    roman: RomanNumerals,
+    current: Box<dyn NumeralSystem>,
+) -> Box<dyn NumeralSystem> {
+    match numeral_system_from_name(name, roman) {
+        Some(chosen) => chosen,
+        None => {
+            println!(
+                "I don't know this numeral system: {}. Using {} numerals.",
+                name,
+                current.name()
+            );
+            current
         }
     }
+}
 
-    // init and populate alien numerals -> roman numerals mapping
-    let mut numeral_mapping: HashMap<String, char> = HashMap::new();
-    for s in statements.iter().filter(|x| x.kind == NumeralStatement) {
-        if let Some((k, v)) = numerals_to_roman(&s.text) {
-            numeral_mapping.insert(k, v.parse().unwrap());
+/// Processes the sentences of an input file with alien numerals standing for symbols of `system`.
+/// Output is printed to stdout
+/// # Arguments
+/// * `system` - numeral system the alien numerals are mapped to
+/// * `sentences` - parsed lines of the input, see parser::parse
+pub fn run_with<N: NumeralSystem + ?Sized>(system: &N, sentences: &[Sentence]) {
+    // populate alien numerals -> symbols mapping
+    let mut kb = KnowledgeBase::new();
+    for s in sentences {
+        if let Some((k, v)) = numeral_mapping_of(system, s.statement()) {
+            kb.add_numeral(&k, v);
         }
     }
-    // init and populate alien units -> value as float (Credits)
-    let mut unit_mapping: HashMap<String, f64> = HashMap::new();
-    for s in statements.iter().filter(|x| x.kind == UnitStatement) {
-        if let Ok((k, v)) = extract_unit_values_from_sentence(&numeral_mapping, &s.text) {
-            if let Some(old_value) = unit_mapping.get(&*k) {
-                if *old_value != v {
+    report_missing_symbols(system, &kb);
+    // populate alien units -> value as exact fraction and exchange rates
+    for s in sentences {
+        match s.statement() {
+            Statement::Unit { .. } => {
+                learn_unit(system, &mut kb, s);
+            }
+            Statement::ExchangeRate { .. } => {
+                learn_exchange_rate(&mut kb, s);
+            }
+            _ => (),
+        }
+    }
+
+    // answer questions, in the order they were asked
+    for s in sentences {
+        match s.statement() {
+            Statement::NumeralSystem { .. }
+            | Statement::Unit { .. }
+            | Statement::ExchangeRate { .. }
+            | Statement::Include { .. }
+            | Statement::Comment => (),
+            Statement::Numeral { .. } if numeral_mapping_of(system, s.statement()).is_some() => (),
+            _ => println!("{}", answer(system, &kb, s.statement())),
+        }
+    }
+}
+
+/// Processes the input line by line, learning definitions as they come.
+/// Questions are answered as soon as every alien numeral, unit and exchange rate
+/// they depend on is known,
+/// questions still waiting at the end of the input are answered with what is known then.
+/// At most MAX_WAITING questions are kept waiting, when another question has to wait
+/// the oldest one is answered with what is known, so memory is bounded by the size of the
+/// knowledge base and every definition checks at most MAX_WAITING questions.
+/// A line like "numbers are base 7" chooses the numeral system for the lines that follow,
+/// alien numerals learned before are forgotten.
+/// Output is printed to stdout
+/// # Arguments
+/// * `roman` - Roman numerals as configured, used unless the input chooses another system
+/// * `input` - the input, with included files in place of the includes
+pub fn run_stream(roman: RomanNumerals, input: Input) -> PccResult<()> {
+    let mut system: Box<dyn NumeralSystem> = Box::new(roman);
+    let mut kb = KnowledgeBase::new();
+    let mut waiting: VecDeque<Sentence> = VecDeque::new();
+
+    for read in input {
+        let s = match included(read)? {
+            Some(s) => s,
+            None => continue,
+        };
+        let learned = match s.statement() {
+            Statement::NumeralSystem { name } => {
+                system = choose_numeral_system(&join_tokens(name), roman, system);
+                kb.clear_numerals();
+                false
+            }
+            Statement::Numeral { .. } => match numeral_mapping_of(&*system, s.statement()) {
+                Some((k, v)) => {
+                    kb.add_numeral(&k, v);
+                    true
+                }
+                None => {
+                    println!("{}", DEFAULT_RESPONSE);
+                    false
+                }
+            },
+            Statement::Unit { .. } => learn_unit(&*system, &mut kb, &s),
+            Statement::ExchangeRate { .. } => learn_exchange_rate(&mut kb, &s),
+            Statement::Comment => false,
+            // skip empty
+            _ if s.text().is_empty() => false,
+            _ if kb.can_answer(&*system, s.statement()) => {
+                println!("{}", answer(&*system, &kb, s.statement()));
+                false
+            }
+            _ => {
+                if waiting.len() == MAX_WAITING {
+                    if let Some(q) = waiting.pop_front() {
+                        println!("{}", answer(&*system, &kb, q.statement()));
+                    }
+                }
+                waiting.push_back(s);
+                false
+            }
+        };
+        if learned {
+            // answer the questions that were waiting for this definition
+            waiting.retain(|q| {
+                if kb.can_answer(&*system, q.statement()) {
+                    println!("{}", answer(&*system, &kb, q.statement()));
+                    return false;
+                }
+                true
+            });
+        }
+    }
+
+    report_missing_symbols(&*system, &kb);
+    for q in waiting {
+        println!("{}", answer(&*system, &kb, q.statement()));
+    }
+    Ok(())
+}
+
+/// Prints which symbols of `system` have no alien numeral, if the system needs all of them,
+/// e.g. positional numbers can only be read if every digit has an alien numeral
+fn report_missing_symbols<N: NumeralSystem + ?Sized>(system: &N, kb: &KnowledgeBase) {
+    let missing = system
+        .required_symbols()
+        .into_iter()
+        .filter(|s| !kb.numerals().values().any(|v| v == s))
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        println!(
+            "Not every symbol of {} numerals has an alien numeral, missing: {}",
+            system.name(),
+            missing.join(", ")
+        );
+    }
+}
+
+/// Learns the value of the unit from a statement about units.
+/// Prints why if the statement can not be understood or changes the value of a known unit.
+/// Returns true if the unit was learned.
+fn learn_unit<N: NumeralSystem + ?Sized>(
+    system: &N,
+    kb: &mut KnowledgeBase,
+    sentence: &Sentence,
+) -> bool {
+    let currency = match sentence.statement() {
+        Statement::Unit { currency, .. } => currency.text(),
+        _ => return false,
+    };
+    match extract_unit_value(system, kb.numerals(), sentence.statement()) {
+        Ok((k, v)) => {
+            if let Some(old) = kb.add_unit(&k, Price::new(v, currency)) {
+                if normalize_currency(old.currency()) != normalize_currency(currency) {
+                    println!(
+                        "\"{}\" has ambiguous value. Old: {} {}, new {} {}. Using new definition.",
+                        k,
+                        old.value(),
+                        old.currency(),
+                        v,
+                        currency
+                    )
+                } else if old.value() != v {
                     println!(
                         "\"{}\" has ambiguous value. Old: {}, new {}. Using new definition.",
-                        k, old_value, v
+                        k,
+                        old.value(),
+                        v
                     )
                 }
             }
-            unit_mapping.insert(k, v);
-        } else {
-            println!("I don't understand this statement about units: {}", s.text)
+            true
+        }
+        Err(e) if e.is::<ZeroAmountError>() || e.is::<OverflowError>() => {
+            println!("{}: {}", sentence.text(), e);
+            false
+        }
+        Err(_) => {
+            println!(
+                "I don't understand this statement about units: {}",
+                sentence.text()
+            );
+            false
         }
     }
+}
 
-    // answer questions
-    for q in statements
-        .iter()
-        .filter(|x| x.kind != UnitStatement && x.kind != NumeralStatement)
-    {
-        if q.kind == HowMuchQuestion {
-            println!("{}", answer_how_much(&numeral_mapping, &q.text));
-        } else if q.kind == HowManyQuestion {
+/// Learns the exchange rate from a statement like "1 Zorkmid is 4 Credits".
+/// Prints why if the statement can not be understood or changes a known rate.
+/// Returns true if the rate was learned.
+fn learn_exchange_rate(kb: &mut KnowledgeBase, sentence: &Sentence) -> bool {
+    match extract_exchange_rate(sentence.statement()) {
+        Ok((currency, v, target)) => {
+            if let Some(old_value) = kb.add_exchange_rate(&currency, v, &target) {
+                if old_value != v {
+                    println!(
+                        "\"{}\" has ambiguous exchange rate. Old: {} {}, new {} {}. Using new definition.",
+                        currency, old_value, target, v, target
+                    )
+                }
+            }
+            true
+        }
+        Err(e)
+            if e.is::<ZeroAmountError>()
+                || e.is::<ZeroExchangeRateError>()
+                || e.is::<OverflowError>() =>
+        {
+            println!("{}: {}", sentence.text(), e);
+            false
+        }
+        Err(_) => {
             println!(
-                "{}",
-                answer_how_many_credits(&numeral_mapping, &unit_mapping, &q.text)
+                "I don't understand this statement about exchange rates: {}",
+                sentence.text()
             );
-        } else {
-            println!("{}", DEFAULT_RESPONSE);
+            false
         }
     }
-    Ok(())
+}
+
+/// Returns the response to a question, or the default response to anything else
+fn answer<N: NumeralSystem + ?Sized>(
+    system: &N,
+    kb: &KnowledgeBase,
+    statement: &Statement,
+) -> String {
+    match statement {
+        Statement::HowMuch { amount } => how_much(system, kb.numerals(), amount),
+        Statement::HowMany { target, words } => how_many(system, kb, target, words),
+        Statement::HowToSay { number } => how_to_say(system, kb.numerals(), number.text()),
+        // an amount of a unit in arabic numerals, e.g. "how many Credits is 3 Silver ?"
+        Statement::Purchase {
+            target,
+            value,
+            currency,
+        } if asks_for_value_of_unit(kb, target, currency) => {
+            how_many(system, kb, target, &[value.clone(), currency.clone()])
+        }
+        Statement::Purchase {
+            target,
+            value,
+            currency,
+        } => purchase(system, kb, target, value.text(), currency.text()),
+        _ => DEFAULT_RESPONSE.to_string(),
+    }
+}
+
+/// Returns true if a purchase question, e.g. "how many Credits is 3 Silver ?", asks for the value
+/// of an amount of a unit instead: "Silver" is a known unit, or the target is a known currency
+/// and "Silver" is not, so an unknown unit is reported as such
+fn asks_for_value_of_unit(kb: &KnowledgeBase, target: &[Token], currency: &Token) -> bool {
+    let target = normalize_whitespace(&join_tokens(target));
+    kb.is_unit(currency.text())
+        || (!kb.is_unit(&target) && kb.is_currency(&target) && !kb.is_currency(currency.text()))
+}
+
+/// Returns (alien_numeral, symbol) if `statement` maps an alien numeral to a symbol of `system`
+fn numeral_mapping_of<N: NumeralSystem + ?Sized>(
+    system: &N,
+    statement: &Statement,
+) -> Option<(String, String)> {
+    match statement {
+        Statement::Numeral { numeral, symbol } => system
+            .parse_symbol(symbol.text())
+            .map(|symbol| (numeral.text().to_string(), symbol)),
+        _ => None,
+    }
+}
+
+/// Returns the texts of `tokens` separated by single spaces
+fn join_tokens(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|t| t.text())
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 /// Returns response to input asking "how much is ..." as String
 /// # Arguments
-/// * `numeral_mapping` - Reference to HashMap mapping alien numerals to chars I,V,X,L,C,D,M
+/// * `system` - numeral system the alien numerals are mapped to
+/// * `numeral_mapping` - Reference to HashMap mapping alien numerals to symbols of `system`, e.g. I,V,X,L,C,D,M or V̅
 /// * `question` - Input question as string that should be answered
 /// # Example
 /// ```
 /// use std::collections::HashMap;
 /// use pangalacticcc::answer_how_much;
-/// let mut nm: HashMap<String, char> = HashMap::new();
-/// nm.insert("pish".to_string(), 'X');
-/// nm.insert("tegj".to_string(), 'L');
-/// nm.insert("glob".to_string(), 'I');
+/// use pangalacticcc::numerals::RomanNumerals;
+/// use pangalacticcc::roman::ParseMode;
+/// let mut nm: HashMap<String, String> = HashMap::new();
+/// nm.insert("pish".to_string(), "X".to_string());
+/// nm.insert("tegj".to_string(), "L".to_string());
+/// nm.insert("glob".to_string(), "I".to_string());
 /// let q = "how much is pish tegj glob glob ?";
-/// assert_eq!(answer_how_much(&nm, q),"pish tegj glob glob is 42".to_string());
+/// let roman = RomanNumerals::new(ParseMode::Strict);
+/// assert_eq!(answer_how_much(&roman, &nm, q),"pish tegj glob glob is 42".to_string());
+/// let q2 = "how much is glob glob pish ?";
+/// let lenient = RomanNumerals::new(ParseMode::Lenient);
+/// assert_eq!(answer_how_much(&lenient, &nm, q2),"glob glob pish is 8 (read IIX as VIII)".to_string());
 /// ```
-pub fn answer_how_much(numeral_mapping: &HashMap<String, char>, question: &str) -> String {
+pub fn answer_how_much<N: NumeralSystem + ?Sized>(
+    system: &N,
+    numeral_mapping: &HashMap<String, String>,
+    question: &str,
+) -> String {
+    match parse_sentence(question).statement() {
+        Statement::HowMuch { amount } => how_much(system, numeral_mapping, amount),
+        _ => DEFAULT_RESPONSE.to_string(),
+    }
+}
+
+/// Returns response to a "how much is ..." question about the alien numerals `amount`.
+/// The amount may also be written in arabic numerals or as symbols of `system`, e.g. XLII,
+/// which is noted in the response.
+fn how_much<N: NumeralSystem + ?Sized>(
+    system: &N,
+    numeral_mapping: &HashMap<String, String>,
+    amount: &[Token],
+) -> String {
+    let words = amount.iter().map(|t| t.text()).collect::<Vec<_>>();
+    match arabic_amount(numeral_mapping, &words) {
+        Some(Ok(value)) => return format!("{} is {} ({})", words[0], value, arabic_note(words[0])),
+        Some(Err(e)) => return format!("{}: {}", words[0], e),
+        None => (),
+    }
     let mut orig: Vec<String> = Vec::new();
-    let mut numerals: Vec<String> = Vec::new();
-    let reserved_tokens = ["?", "how", "much", "is"];
+    let mut symbols: Vec<String> = Vec::new();
+    let mut literal: Vec<&str> = Vec::new();
 
-    for word in question.split(' ') {
-        if numeral_mapping.get(word).is_none()
-            && !reserved_tokens.contains(&word)
-            && !word.is_empty()
-        {
-            print!("{} could not be translated. ", word)
+    for word in words {
+        match amount_symbol(system, numeral_mapping, word) {
+            Some((symbol, is_literal)) => {
+                if is_literal {
+                    literal.push(word);
+                }
+                symbols.push(symbol);
+                orig.push(word.to_string());
+            }
+            None => print!("{} could not be translated. ", word),
         }
-        if let Some(value) = numeral_mapping.get(word) {
-            numerals.push(
-                value
-                    .to_string()
-                    .parse::<Roman>()
-                    .expect("something went wrong while parsing")
-                    .get_representation(),
-            );
-            orig.push(word.to_string());
+    }
+
+    let numerals = symbols.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+    match system.read(&numerals) {
+        Ok(reading) => {
+            let notes = literal_note(system, &literal)
+                .into_iter()
+                .chain(reading.note().map(|note| note.to_string()))
+                .collect::<Vec<_>>();
+            if notes.is_empty() {
+                format!("{} is {}", orig.join(" "), reading.value())
+            } else {
+                format!(
+                    "{} is {} ({})",
+                    orig.join(" "),
+                    reading.value(),
+                    notes.join("; ")
+                )
+            }
         }
+        Err(e) => explain_invalid_number(system, &orig, &numerals, &e),
     }
+}
+
+/// Returns a note that `word` was read as a number in arabic numerals
+fn arabic_note(word: &str) -> String {
+    format!("read {} as arabic number", word)
+}
 
-    if let Ok(result) = numerals.join("").parse::<Roman>() {
-        format!("{} is {}", orig.join(" "), result.get_value())
+/// Returns a note that the words `literal` of an amount were read as symbols of `system`,
+/// or None if there are none
+fn literal_note<N: NumeralSystem + ?Sized>(system: &N, literal: &[&str]) -> Option<String> {
+    if literal.is_empty() {
+        None
     } else {
-        format!(
-            "I don't know how to interpret this number: {} -> {}",
-            orig.join(" "),
-            numerals.join("")
-        )
+        Some(format!(
+            "read {} as {} numerals",
+            literal.join(" "),
+            system.name()
+        ))
     }
 }
 
-/// Returns response to input asking "how many credits is ..." as String
+/// Returns response explaining why the alien numerals `words` could not be read,
+/// naming the word that caused the error if possible.
+/// # Arguments
+/// * `system` - numeral system the alien numerals are mapped to
+/// * `words` - alien numerals as found in the input
+/// * `symbols` - symbols the alien numerals map to, in the same order
+/// * `error` - error returned when reading the symbols
+fn explain_invalid_number<N: NumeralSystem + ?Sized, S: AsRef<str>>(
+    system: &N,
+    words: &[S],
+    symbols: &[&str],
+    error: &NumeralError,
+) -> String {
+    let words = words.iter().map(|w| w.as_ref()).collect::<Vec<_>>();
+    let reason = match error.symbol().and_then(|i| words.get(i)) {
+        Some(word) => format!("{}: {}", word, error),
+        None => error.to_string(),
+    };
+    format!(
+        "I don't know how to interpret this number: {} -> {} ({})",
+        words.join(" "),
+        system.join(symbols),
+        reason
+    )
+}
+
+/// Returns response to input asking "how many credits is ..." as String.
+/// Other currencies can not be converted to, as there are no exchange rates.
 /// # Arguments
-/// * `numeral_mapping` - Reference to HashMap mapping alien numerals to chars I,V,X,L,C,D,M
+/// * `system` - numeral system the alien numerals are mapped to
+/// * `numeral_mapping` - Reference to HashMap mapping alien numerals to symbols of `system`, e.g. I,V,X,L,C,D,M or V̅
+/// * `unit_mapping` - Reference to HashMap mapping alien units to their value in Credits
 /// * `question` - Input question as string that should be answered
 /// # Example
 /// ```
 /// use std::collections::HashMap;
 /// use pangalacticcc::{answer_how_many_credits};
-/// let mut nm: HashMap<String, char> = HashMap::new();
-/// nm.insert("pish".to_string(), 'X');
-/// nm.insert("prok".to_string(), 'V');
-/// nm.insert("glob".to_string(), 'I');
-/// let mut um: HashMap<String, f64> = HashMap::new();
-/// um.insert("Iron".to_string(), 195.5);
+/// use pangalacticcc::numerals::RomanNumerals;
+/// use pangalacticcc::rational::Rational;
+/// let mut nm: HashMap<String, String> = HashMap::new();
+/// nm.insert("pish".to_string(), "X".to_string());
+/// nm.insert("prok".to_string(), "V".to_string());
+/// nm.insert("glob".to_string(), "I".to_string());
+/// let mut um: HashMap<String, Rational> = HashMap::new();
+/// um.insert("Iron".to_string(), Rational::new(391, 2));
+/// let roman = RomanNumerals::default();
 /// let q = "how many Credits is glob prok Iron ?";
-/// assert_eq!(answer_how_many_credits(&nm, &um, q), "glob prok Iron is 782 Credits".to_string());
+/// assert_eq!(answer_how_many_credits(&roman, &nm, &um, q), "glob prok Iron is 782 Credits".to_string());
 /// let q2 = "how many Credits is bla prok Iron ?";
-/// assert_eq!(answer_how_many_credits(&nm, &um, q2), "Not everything could be translated to roman numerals: bla prok".to_string());
+/// assert_eq!(answer_how_many_credits(&roman, &nm, &um, q2), "Not everything could be translated to roman numerals: bla prok".to_string());
 /// let q3 = "how many Credits is glob prok Fish ?";
-/// assert_eq!(answer_how_many_credits(&nm, &um, q3), "This unit is unkown to me: Fish".to_string());
+/// assert_eq!(answer_how_many_credits(&roman, &nm, &um, q3), "This unit is unkown to me: Fish".to_string());
+/// um.insert("Dirt".to_string(), Rational::new(10, 3));
+/// let q4 = "how many Credits is glob glob glob Dirt ?";
+/// assert_eq!(answer_how_many_credits(&roman, &nm, &um, q4), "glob glob glob Dirt is 10 Credits".to_string());
 /// ```
-pub fn answer_how_many_credits(
-    numeral_mapping: &HashMap<String, char>,
-    unit_mapping: &HashMap<String, f64>,
+pub fn answer_how_many_credits<N: NumeralSystem + ?Sized>(
+    system: &N,
+    numeral_mapping: &HashMap<String, String>,
+    unit_mapping: &HashMap<String, Rational>,
     question: &str,
 ) -> String {
-    // todo refactor
-    // todo: error on incomplete mappings!
-    let default = "I have no idea what you are talking about".to_string();
-    let amount_unit = question
-        .split("how many Credits is ")
-        .into_iter()
-        .filter_map(|element| element.trim_start().trim_end().strip_suffix('?'))
-        .map(|x| x.trim_end())
-        .collect::<Vec<_>>();
+    let kb = knowledge_of(numeral_mapping, unit_mapping);
+    match parse_sentence(question).statement() {
+        Statement::HowMany { target, words } => how_many(system, &kb, target, words),
+        Statement::Purchase {
+            target,
+            value,
+            currency,
+        } if asks_for_value_of_unit(&kb, target, currency) => {
+            how_many(system, &kb, target, &[value.clone(), currency.clone()])
+        }
+        _ => DEFAULT_RESPONSE.to_string(),
+    }
+}
 
-    // return default response if sentence is of different structure
-    let mut amount = match amount_unit.get(0) {
-        None => return default,
-        Some(a) => a.split(' ').collect::<Vec<_>>(),
+/// Returns response to input asking how much of a unit can be bought, e.g.
+/// "how much Silver can I buy for 100 Credits ?" or "how many Silver is 100 Credits ?".
+/// The quantity is given in alien numerals if possible, followed by the Credits left.
+/// # Arguments
+/// * `system` - numeral system the alien numerals are mapped to
+/// * `numeral_mapping` - Reference to HashMap mapping alien numerals to symbols of `system`, e.g. I,V,X,L,C,D,M or V̅
+/// * `unit_mapping` - Reference to HashMap mapping alien units to their value in Credits
+/// * `question` - Input question as string that should be answered
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use pangalacticcc::answer_how_much_can_i_buy;
+/// use pangalacticcc::numerals::RomanNumerals;
+/// use pangalacticcc::rational::Rational;
+/// let mut nm: HashMap<String, String> = HashMap::new();
+/// nm.insert("prok".to_string(), "V".to_string());
+/// nm.insert("glob".to_string(), "I".to_string());
+/// let mut um: HashMap<String, Rational> = HashMap::new();
+/// um.insert("Silver".to_string(), Rational::from(17));
+/// let roman = RomanNumerals::default();
+/// let q = "how much Silver can I buy for 100 Credits ?";
+/// assert_eq!(answer_how_much_can_i_buy(&roman, &nm, &um, q), "100 Credits buy prok Silver, 15 Credits left".to_string());
+/// let q2 = "how many Silver is 102 Credits ?";
+/// assert_eq!(answer_how_much_can_i_buy(&roman, &nm, &um, q2), "102 Credits buy prok glob Silver".to_string());
+/// ```
+pub fn answer_how_much_can_i_buy<N: NumeralSystem + ?Sized>(
+    system: &N,
+    numeral_mapping: &HashMap<String, String>,
+    unit_mapping: &HashMap<String, Rational>,
+    question: &str,
+) -> String {
+    let kb = knowledge_of(numeral_mapping, unit_mapping);
+    match parse_sentence(question).statement() {
+        Statement::Purchase {
+            target,
+            value,
+            currency,
+        } => purchase(system, &kb, target, value.text(), currency.text()),
+        _ => DEFAULT_RESPONSE.to_string(),
+    }
+}
+
+/// Returns a KnowledgeBase knowing the alien numerals and the units, priced in Credits
+fn knowledge_of(
+    numeral_mapping: &HashMap<String, String>,
+    unit_mapping: &HashMap<String, Rational>,
+) -> KnowledgeBase {
+    let mut kb = KnowledgeBase::new();
+    for (numeral, symbol) in numeral_mapping {
+        kb.add_numeral(numeral, symbol.clone());
+    }
+    for (unit, value) in unit_mapping {
+        kb.add_unit(unit, Price::new(*value, "Credits"));
+    }
+    kb
+}
+
+/// Returns response to a question asking how much of `target` can be bought
+/// for `value` `currency`, value is a decimal number.
+/// If the target is a unit, the whole quantity is given in alien numerals if possible,
+/// followed by what is left of the money. Otherwise the target is a currency.
+fn purchase<N: NumeralSystem + ?Sized>(
+    system: &N,
+    kb: &KnowledgeBase,
+    target: &[Token],
+    value: &str,
+    currency: &str,
+) -> String {
+    let too_large = || format!("{} {}: {}", value, currency, OverflowError);
+    let money = match value.parse::<Rational>() {
+        Ok(money) => money,
+        Err(_) => return too_large(),
     };
-    // split amount and unit
-    let unit = &amount.pop().unwrap();
+    let target = normalize_whitespace(&join_tokens(target));
+    if kb.is_worthless(&target) {
+        return format!(
+            "I can not convert {} to {}, {} has no value",
+            currency, target, target
+        );
+    }
+    if !kb.is_unit(&target) {
+        return match kb.convert(money, currency, &target) {
+            Some(Ok(bought)) => format!(
+                "{} {} buy {} {}",
+                value,
+                currency,
+                bought,
+                kb.currency_name(&target)
+            ),
+            Some(Err(_)) => too_large(),
+            None => format!("I don't know how to convert {} to {}", currency, target),
+        };
+    }
+    match kb.convert_to_unit(money, currency, &target) {
+        Some(Ok(quantity)) => {
+            let whole = Rational::from(quantity.numer() / quantity.denom());
+            // the money is worth nothing if none of the unit can be bought
+            let left = if whole == Rational::from(0) {
+                Some(money)
+            } else {
+                money
+                    .checked_mul(whole)
+                    .and_then(|spent| spent.checked_div(quantity))
+                    .and_then(|spent| money.checked_sub(spent))
+            };
+            let left = match left {
+                Some(left) => left,
+                None => return too_large(),
+            };
+            let bought = format!(
+                "{} {} buy {} {}",
+                value,
+                currency,
+                to_alien_numerals(system, kb.numerals(), whole),
+                target
+            );
+            if left == Rational::from(0) {
+                bought
+            } else {
+                format!("{}, {} {} left", bought, left, currency)
+            }
+        }
+        Some(Err(_)) => too_large(),
+        None => format!("I don't know how to convert {} to {}", currency, target),
+    }
+}
 
-    let roman_number = amount
-        .iter()
-        .filter_map(|x| numeral_mapping.get(*x))
-        .collect::<String>();
+/// Returns response to a "how many <target> is ..." question about `words`,
+/// alien numerals followed by a unit. The target is a currency or another unit,
+/// amounts of units are given in alien numerals if possible.
+fn how_many<N: NumeralSystem + ?Sized>(
+    system: &N,
+    kb: &KnowledgeBase,
+    target: &[Token],
+    words: &[Token],
+) -> String {
+    // todo: error on incomplete mappings!
+    let (amount, unit) = match kb.split_amount_and_unit(words) {
+        // a unit without an amount, e.g. "how many Credits is Silver ?"
+        Some(([], _)) => return DEFAULT_RESPONSE.to_string(),
+        Some(split) => split,
+        None => return DEFAULT_RESPONSE.to_string(),
+    };
+    let amount = amount.iter().map(|t| t.text()).collect::<Vec<_>>();
+
+    let mut symbols: Vec<String> = Vec::new();
+    let mut literal: Vec<&str> = Vec::new();
+    for word in &amount {
+        if let Some((symbol, is_literal)) = amount_symbol(system, kb.numerals(), word) {
+            if is_literal {
+                literal.push(word);
+            }
+            symbols.push(symbol);
+        }
+    }
+    let too_large = || format!("{} {}: {}", amount.join(" "), unit, OverflowError);
+    let arabic = match arabic_amount(kb.numerals(), &amount) {
+        Some(Ok(value)) => Some(value),
+        Some(Err(_)) => return too_large(),
+        None => None,
+    };
 
     // return early if alien numeral could not be converted
-    if roman_number.len() != amount.len() {
+    if arabic.is_none() && symbols.len() != amount.len() {
         return format!(
-            "Not everything could be translated to roman numerals: {}",
+            "Not everything could be translated to {} numerals: {}",
+            system.name(),
             amount.join(" ")
         );
     }
 
-    if let Some(value) = unit_mapping.get(*unit) {
-        if let Ok(amount_parsed) = roman_number.parse::<Roman>() {
+    if let Some(price) = get_ignore_case(kb.units(), &unit) {
+        let (amount_value, note) = match arabic {
+            Some(value) => (value, Some(arabic_note(amount[0]))),
+            None => {
+                let symbols = symbols.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+                match system.read(&symbols) {
+                    Ok(reading) => (reading.value(), literal_note(system, &literal)),
+                    Err(e) => return explain_invalid_number(system, &amount, &symbols, &e),
+                }
+            }
+        };
+        let note = note.map(|note| format!(" ({})", note)).unwrap_or_default();
+        let value = match amount_value.checked_mul(price.value()) {
+            Some(value) => value,
+            None => return too_large(),
+        };
+        let target = normalize_whitespace(&join_tokens(target));
+        if kb.is_worthless(&target) {
             return format!(
-                "{} {} is {} Credits",
+                "I can not convert {} to {}, {} has no value",
+                unit, target, target
+            );
+        }
+        if kb.is_unit(&target) {
+            return match kb.convert_to_unit(value, price.currency(), &target) {
+                Some(Ok(value)) => format!(
+                    "{} {} is {} {}{}",
+                    amount.join(" "),
+                    unit,
+                    to_alien_numerals(system, kb.numerals(), value),
+                    target,
+                    note
+                ),
+                Some(Err(_)) => too_large(),
+                None => format!("I don't know how to convert {} to {}", unit, target),
+            };
+        }
+        return match kb.convert(value, price.currency(), &target) {
+            Some(Ok(value)) => format!(
+                "{} {} is {} {}{}",
                 amount.join(" "),
                 unit,
-                amount_parsed.get_value() as f64 * value
-            );
+                value,
+                kb.currency_name(&target),
+                note
+            ),
+            Some(Err(_)) => too_large(),
+            None => format!(
+                "I don't know how to convert {} to {}",
+                price.currency(),
+                target
+            ),
+        };
+    }
+    // couldn't find unit in map
+    format!("This unit is unkown to me: {}", unit)
+}
+
+/// Returns response to a question asking how to say `number`, a whole number in arabic numerals,
+/// in alien numerals. Names the symbols without an alien numeral if there are any.
+/// # Arguments
+/// * `system` - numeral system the alien numerals are mapped to
+/// * `numeral_mapping` - Reference to HashMap mapping alien numerals to symbols of `system`
+/// * `number` - the number to say
+fn how_to_say<N: NumeralSystem + ?Sized>(
+    system: &N,
+    numeral_mapping: &HashMap<String, String>,
+    number: &str,
+) -> String {
+    let symbols = match number.parse::<i128>() {
+        Ok(value) => match system.render(Rational::from(value)) {
+            Ok(symbols) => symbols,
+            Err(e) => return e.to_string(),
+        },
+        Err(_) => return format!("{} is too large", number),
+    };
+    match alien_numerals_for(numeral_mapping, &symbols) {
+        Ok(words) => format!("{} is {}", number, words.join(" ")),
+        Err(missing) => format!(
+            "{} can not be said in alien numerals, no alien numeral for: {}",
+            number,
+            missing.join(", ")
+        ),
+    }
+}
+
+/// Returns the alien numerals for `symbols`, or the symbols without alien numeral.
+/// At every position the alien numeral standing for the most symbols is used,
+/// e.g. "pish" for IX if pish is IX, so compound alien numerals are used where possible.
+fn alien_numerals_for<'a>(
+    numeral_mapping: &'a HashMap<String, String>,
+    symbols: &'a [String],
+) -> Result<Vec<&'a str>, Vec<&'a str>> {
+    let mut words = Vec::new();
+    let mut missing: Vec<&str> = Vec::new();
+    let mut start = 0;
+    while start < symbols.len() {
+        let longest = (start + 1..=symbols.len()).rev().find_map(|end| {
+            alien_numeral_for(numeral_mapping, &symbols[start..end].concat()).map(|w| (w, end))
+        });
+        match longest {
+            Some((word, end)) => {
+                words.push(word);
+                start = end;
+            }
+            None => {
+                if !missing.contains(&symbols[start].as_str()) {
+                    missing.push(&symbols[start]);
+                }
+                start += 1;
+            }
         }
+    }
+    if missing.is_empty() {
+        Ok(words)
     } else {
-        // couldn't find unit in map
-        return format!("This unit is unkown to me: {}", unit);
+        Err(missing)
+    }
+}
+
+/// Returns the alien numeral standing for `symbol`, or None if there is none.
+/// If several alien numerals stand for the same symbol, the first in alphabetical order is used.
+fn alien_numeral_for<'a>(
+    numeral_mapping: &'a HashMap<String, String>,
+    symbol: &str,
+) -> Option<&'a str> {
+    numeral_mapping
+        .iter()
+        .filter(|(_, s)| *s == symbol)
+        .map(|(word, _)| word.as_str())
+        .min()
+}
+
+/// Returns `value` written in alien numerals standing for symbols of `system`, or in arabic
+/// numerals if it is not a whole number or one of its symbols has no alien numeral.
+/// # Arguments
+/// * `system` - numeral system the alien numerals are mapped to
+/// * `numeral_mapping` - Reference to HashMap mapping alien numerals to symbols of `system`
+/// * `value` - the number to write
+fn to_alien_numerals<N: NumeralSystem + ?Sized>(
+    system: &N,
+    numeral_mapping: &HashMap<String, String>,
+    value: Rational,
+) -> String {
+    if value.is_integer() {
+        if let Ok(symbols) = system.render(value) {
+            if let Ok(words) = alien_numerals_for(numeral_mapping, &symbols) {
+                return words.join(" ");
+            }
+        }
     }
-    default
+    value.to_string()
 }
 
 /// Returns a BufReader for `path` on success.
@@ -322,77 +989,391 @@ mod tests {
 
     #[test]
     fn test_answer_how_much_example_42() {
-        let mut hm: HashMap<String, char> = HashMap::new();
-        hm.insert("glob".to_string(), 'I');
-        hm.insert("prok".to_string(), 'V');
-        hm.insert("tegj".to_string(), 'L');
-        hm.insert("pish".to_string(), 'X');
+        let mut hm: HashMap<String, String> = HashMap::new();
+        hm.insert("glob".to_string(), "I".to_string());
+        hm.insert("prok".to_string(), "V".to_string());
+        hm.insert("tegj".to_string(), "L".to_string());
+        hm.insert("pish".to_string(), "X".to_string());
         let question = "how much is pish tegj glob glob ?";
         let expected = "pish tegj glob glob is 42";
-        let result = answer_how_much(&hm, question);
+        let result = answer_how_much(&RomanNumerals::default(), &hm, question);
         assert_eq!(expected, result)
     }
 
     #[test]
     fn test_answer_how_much_bla_8() {
-        let mut hm: HashMap<String, char> = HashMap::new();
-        hm.insert("bla".to_string(), 'I');
-        hm.insert("blub".to_string(), 'V');
-        hm.insert("blubber".to_string(), 'L');
+        let mut hm: HashMap<String, String> = HashMap::new();
+        hm.insert("bla".to_string(), "I".to_string());
+        hm.insert("blub".to_string(), "V".to_string());
+        hm.insert("blubber".to_string(), "L".to_string());
         let question = "how much is blub bla bla bla ?";
         let expected = "blub bla bla bla is 8";
-        let result = answer_how_much(&hm, question);
+        let result = answer_how_much(&RomanNumerals::default(), &hm, question);
         assert_eq!(expected, result)
     }
 
     #[test]
     fn test_answer_how_much_bla_invalid() {
-        let mut hm: HashMap<String, char> = HashMap::new();
-        hm.insert("bla".to_string(), 'I');
-        hm.insert("blub".to_string(), 'V');
-        hm.insert("blubber".to_string(), 'L');
+        let mut hm: HashMap<String, String> = HashMap::new();
+        hm.insert("bla".to_string(), "I".to_string());
+        hm.insert("blub".to_string(), "V".to_string());
+        hm.insert("blubber".to_string(), "L".to_string());
         let question = "how much is blub blubber ?"; // VL -> ParseRomanNumeralError
-        let expected = "I don't know how to interpret this number: blub blubber -> VL";
-        let result = answer_how_much(&hm, question);
+        let expected = "I don't know how to interpret this number: blub blubber -> VL \
+            (blub: V may never precede a larger symbol)";
+        let result = answer_how_much(&RomanNumerals::default(), &hm, question);
         assert_eq!(expected, result)
     }
 
     #[test]
     fn test_answer_how_many_example_silver_86() {
-        let mut nm: HashMap<String, char> = HashMap::new();
-        nm.insert("glob".to_string(), 'I');
-        nm.insert("prok".to_string(), 'V');
-        let mut um: HashMap<String, f64> = HashMap::new();
-        um.insert("Silver".to_string(), 21.5);
+        let mut nm: HashMap<String, String> = HashMap::new();
+        nm.insert("glob".to_string(), "I".to_string());
+        nm.insert("prok".to_string(), "V".to_string());
+        let mut um: HashMap<String, Rational> = HashMap::new();
+        um.insert("Silver".to_string(), Rational::new(43, 2));
         let question = "how many Credits is glob prok Silver ?";
         let expected = "glob prok Silver is 86 Credits";
-        let result = answer_how_many_credits(&nm, &um, question);
+        let result = answer_how_many_credits(&RomanNumerals::default(), &nm, &um, question);
         assert_eq!(expected, result)
     }
 
     #[test]
     fn test_answer_how_many_example_gold_57800() {
-        let mut nm: HashMap<String, char> = HashMap::new();
-        nm.insert("glob".to_string(), 'I');
-        nm.insert("prok".to_string(), 'V');
-        let mut um: HashMap<String, f64> = HashMap::new();
-        um.insert("Gold".to_string(), 14450.0);
+        let mut nm: HashMap<String, String> = HashMap::new();
+        nm.insert("glob".to_string(), "I".to_string());
+        nm.insert("prok".to_string(), "V".to_string());
+        let mut um: HashMap<String, Rational> = HashMap::new();
+        um.insert("Gold".to_string(), Rational::from(14450));
         let question = "how many Credits is glob prok Gold ?";
         let expected = "glob prok Gold is 57800 Credits";
-        let result = answer_how_many_credits(&nm, &um, question);
+        let result = answer_how_many_credits(&RomanNumerals::default(), &nm, &um, question);
         assert_eq!(expected, result)
     }
 
     #[test]
     fn test_answer_how_many_example_iron_782() {
-        let mut nm: HashMap<String, char> = HashMap::new();
-        nm.insert("glob".to_string(), 'I');
-        nm.insert("prok".to_string(), 'V');
-        let mut um: HashMap<String, f64> = HashMap::new();
-        um.insert("Iron".to_string(), 195.5);
+        let mut nm: HashMap<String, String> = HashMap::new();
+        nm.insert("glob".to_string(), "I".to_string());
+        nm.insert("prok".to_string(), "V".to_string());
+        let mut um: HashMap<String, Rational> = HashMap::new();
+        um.insert("Iron".to_string(), Rational::new(391, 2));
         let question = "how many Credits is glob prok Iron ?";
         let expected = "glob prok Iron is 782 Credits";
-        let result = answer_how_many_credits(&nm, &um, question);
+        let result = answer_how_many_credits(&RomanNumerals::default(), &nm, &um, question);
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn test_answer_how_many_without_amount() {
+        let mut nm: HashMap<String, String> = HashMap::new();
+        nm.insert("glob".to_string(), "I".to_string());
+        let mut um: HashMap<String, Rational> = HashMap::new();
+        um.insert("Silver".to_string(), Rational::from(17));
+        for question in [
+            "how many Credits is Silver ?",
+            "how many Credits is \"Silver\" ?",
+        ] {
+            assert_eq!(
+                DEFAULT_RESPONSE,
+                answer_how_many_credits(&RomanNumerals::default(), &nm, &um, question)
+            );
+        }
+    }
+
+    #[test]
+    fn test_answer_how_many_dirt_exact() {
+        let mut nm: HashMap<String, String> = HashMap::new();
+        nm.insert("glob".to_string(), "I".to_string());
+        nm.insert("prok".to_string(), "V".to_string());
+        let mut um: HashMap<String, Rational> = HashMap::new();
+        um.insert("Dirt".to_string(), Rational::new(10, 3));
+        let question = "how many Credits is glob glob glob Dirt ?";
+        assert_eq!(
+            "glob glob glob Dirt is 10 Credits",
+            answer_how_many_credits(&RomanNumerals::default(), &nm, &um, question)
+        );
+        let question = "how many Credits is glob glob Dirt ?";
+        assert_eq!(
+            "glob glob Dirt is 20/3 Credits",
+            answer_how_many_credits(&RomanNumerals::default(), &nm, &um, question)
+        )
+    }
+
+    #[test]
+    fn test_answer_how_much_vinculum() {
+        let mut hm: HashMap<String, String> = HashMap::new();
+        hm.insert("glob".to_string(), "I".to_string());
+        hm.insert("prok".to_string(), "V\u{305}".to_string());
+        hm.insert("zorg".to_string(), "X\u{305}".to_string());
+        hm.insert("ziff".to_string(), "M".to_string());
+        let question = "how much is zorg prok ziff ziff glob ?";
+        let expected = "zorg prok ziff ziff glob is 17001";
+        let result = answer_how_much(&RomanNumerals::default(), &hm, question);
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn test_answer_how_many_extended() {
+        let mut nm: HashMap<String, String> = HashMap::new();
+        nm.insert("glob".to_string(), "I".to_string());
+        nm.insert("grok".to_string(), "V\u{305}".to_string());
+        let mut um: HashMap<String, Rational> = HashMap::new();
+        um.insert("Sand".to_string(), Rational::new(1, 2));
+        let question = "how many Credits is grok glob Sand ?";
+        let expected = "grok glob Sand is 2500.5 Credits";
+        let result = answer_how_many_credits(&RomanNumerals::default(), &nm, &um, question);
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn test_answer_how_much_lenient() {
+        let mut hm: HashMap<String, String> = HashMap::new();
+        hm.insert("glob".to_string(), "I".to_string());
+        hm.insert("pish".to_string(), "X".to_string());
+        let question = "how much is pish glob glob pish ?";
+        let expected = "pish glob glob pish is 18 (read XIIX as XVIII)";
+        let result = answer_how_much(&RomanNumerals::new(ParseMode::Lenient), &hm, question);
+        assert_eq!(expected, result);
+        let question = "how much is pish glob ?";
+        let expected = "pish glob is 11";
+        let result = answer_how_much(&RomanNumerals::new(ParseMode::Lenient), &hm, question);
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn test_answer_how_much_strict_rejects_additive() {
+        let mut hm: HashMap<String, String> = HashMap::new();
+        hm.insert("glob".to_string(), "I".to_string());
+        let question = "how much is glob glob glob glob ?";
+        let expected = "I don't know how to interpret this number: glob glob glob glob -> IIII \
+            (glob: more than three repeats of I)";
+        let result = answer_how_much(&RomanNumerals::default(), &hm, question);
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn test_answer_how_much_explains_non_canonical() {
+        let mut hm: HashMap<String, String> = HashMap::new();
+        hm.insert("glob".to_string(), "I".to_string());
+        hm.insert("pish".to_string(), "X".to_string());
+        let question = "how much is glob pish glob ?";
+        let expected = "I don't know how to interpret this number: glob pish glob -> IXI \
+            (symbols are not in canonical order, expected X)";
+        let result = answer_how_much(&RomanNumerals::default(), &hm, question);
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn test_answer_how_much_explains_extended_symbol() {
+        let mut hm: HashMap<String, String> = HashMap::new();
+        hm.insert("glob".to_string(), "I".to_string());
+        hm.insert("grok".to_string(), "V\u{305}".to_string());
+        let question = "how much is glob grok grok ?";
+        let expected =
+            "I don't know how to interpret this number: glob grok grok -> IV\u{305}V\u{305} \
+            (glob: I may not be subtracted from V\u{305})";
+        let result = answer_how_much(&RomanNumerals::default(), &hm, question);
         assert_eq!(expected, result)
     }
+
+    #[test]
+    fn test_answer_how_many_explains_invalid_number() {
+        let mut nm: HashMap<String, String> = HashMap::new();
+        nm.insert("glob".to_string(), "I".to_string());
+        nm.insert("prok".to_string(), "V".to_string());
+        let mut um: HashMap<String, Rational> = HashMap::new();
+        um.insert("Silver".to_string(), Rational::from(17));
+        let question = "how many Credits is glob prok prok Silver ?";
+        let expected = "I don't know how to interpret this number: glob prok prok -> IVV \
+            (prok: V may never be repeated)";
+        let result = answer_how_many_credits(&RomanNumerals::default(), &nm, &um, question);
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn test_answer_how_many_credits_multi_word_unit() {
+        let mut nm: HashMap<String, String> = HashMap::new();
+        nm.insert("glob".to_string(), "I".to_string());
+        nm.insert("prok".to_string(), "V".to_string());
+        let mut um: HashMap<String, Rational> = HashMap::new();
+        um.insert("Dark Matter".to_string(), Rational::from(17));
+        um.insert("prok Dust".to_string(), Rational::from(10));
+        let roman = RomanNumerals::default();
+        assert_eq!(
+            answer_how_many_credits(
+                &roman,
+                &nm,
+                &um,
+                "how many Credits is glob prok Dark Matter ?"
+            ),
+            "glob prok Dark Matter is 68 Credits"
+        );
+        assert_eq!(
+            answer_how_many_credits(&roman, &nm, &um, "how many Credits is glob prok Dust ?"),
+            "glob prok Dust is 10 Credits"
+        );
+        assert_eq!(
+            answer_how_many_credits(&roman, &nm, &um, "how many Credits is glob \"prok Dust\" ?"),
+            "glob prok Dust is 10 Credits"
+        );
+        assert_eq!(
+            answer_how_many_credits(&roman, &nm, &um, "how many Credits is bla Dark Matter ?"),
+            "Not everything could be translated to roman numerals: bla"
+        );
+    }
+
+    #[test]
+    fn test_answer_ignores_case_and_whitespace() {
+        let mut nm: HashMap<String, String> = HashMap::new();
+        nm.insert("glob".to_string(), "I".to_string());
+        nm.insert("prok".to_string(), "V".to_string());
+        let mut um: HashMap<String, Rational> = HashMap::new();
+        um.insert("silver".to_string(), Rational::from(17));
+        let roman = RomanNumerals::default();
+        assert_eq!(
+            answer_how_much(&roman, &nm, "How Much IS  GLOB\tprok?"),
+            "GLOB prok is 4"
+        );
+        assert_eq!(
+            answer_how_many_credits(&roman, &nm, &um, "HOW many credits  is glob Silver ?"),
+            "glob Silver is 17 Credits"
+        );
+    }
+
+    #[test]
+    fn test_answer_how_many_unit_in_alien_numerals() {
+        let mut nm: HashMap<String, String> = HashMap::new();
+        nm.insert("glob".to_string(), "I".to_string());
+        nm.insert("prok".to_string(), "V".to_string());
+        nm.insert("pish".to_string(), "X".to_string());
+        let mut um: HashMap<String, Rational> = HashMap::new();
+        um.insert("Silver".to_string(), Rational::from(17));
+        um.insert("Copper".to_string(), Rational::from(34));
+        um.insert("Iron".to_string(), Rational::new(391, 2));
+        let roman = RomanNumerals::default();
+        assert_eq!(
+            answer_how_many_credits(&roman, &nm, &um, "how many Silver is prok Copper ?"),
+            "prok Copper is pish Silver"
+        );
+        assert_eq!(
+            answer_how_many_credits(&roman, &nm, &um, "how many Copper is glob Silver ?"),
+            "glob Silver is 0.5 Copper"
+        );
+        // C has no alien numeral
+        assert_eq!(
+            answer_how_many_credits(&roman, &nm, &um, "how many Silver is pish Iron ?"),
+            "pish Iron is 115 Silver"
+        );
+    }
+
+    #[test]
+    fn test_to_alien_numerals() {
+        let mut nm: HashMap<String, String> = HashMap::new();
+        nm.insert("glob".to_string(), "I".to_string());
+        nm.insert("blub".to_string(), "I".to_string());
+        nm.insert("prok".to_string(), "V".to_string());
+        let roman = RomanNumerals::default();
+        assert_eq!(
+            to_alien_numerals(&roman, &nm, Rational::from(4)),
+            "blub prok"
+        );
+        assert_eq!(to_alien_numerals(&roman, &nm, Rational::from(0)), "0");
+        assert_eq!(to_alien_numerals(&roman, &nm, Rational::new(3, 2)), "1.5");
+    }
+
+    #[test]
+    fn test_how_to_say() {
+        let mut nm: HashMap<String, String> = HashMap::new();
+        nm.insert("glob".to_string(), "I".to_string());
+        nm.insert("pish".to_string(), "X".to_string());
+        nm.insert("tegj".to_string(), "L".to_string());
+        let roman = RomanNumerals::default();
+        assert_eq!(how_to_say(&roman, &nm, "42"), "42 is pish tegj glob glob");
+        assert_eq!(
+            how_to_say(&roman, &nm, "1903"),
+            "1903 can not be said in alien numerals, no alien numeral for: M, C"
+        );
+        assert_eq!(
+            how_to_say(&roman, &nm, "1000000000000000000000000000000000000000"),
+            "1000000000000000000000000000000000000000 is too large"
+        );
+    }
+
+    #[test]
+    fn test_how_to_say_compound_numerals() {
+        let mut nm: HashMap<String, String> = HashMap::new();
+        nm.insert("pish".to_string(), "IX".to_string());
+        let roman = RomanNumerals::default();
+        assert_eq!(how_to_say(&roman, &nm, "9"), "9 is pish");
+        assert_eq!(
+            how_to_say(&roman, &nm, "19"),
+            "19 can not be said in alien numerals, no alien numeral for: X"
+        );
+        // the longest match is used first
+        nm.insert("glob".to_string(), "I".to_string());
+        nm.insert("krok".to_string(), "X".to_string());
+        nm.insert("tegj".to_string(), "XL".to_string());
+        assert_eq!(how_to_say(&roman, &nm, "49"), "49 is tegj pish");
+        assert_eq!(
+            how_to_say(&roman, &nm, "8"),
+            "8 can not be said in alien numerals, no alien numeral for: V"
+        );
+        assert_eq!(
+            to_alien_numerals(&roman, &nm, Rational::from(19)),
+            "krok pish"
+        );
+    }
+
+    #[test]
+    fn test_purchase_in_other_currency() {
+        let mut kb = KnowledgeBase::new();
+        kb.add_numeral("glob", "I".to_string());
+        kb.add_unit("Gold", Price::new(Rational::from(3), "Zorkmids"));
+        kb.add_exchange_rate("Zorkmid", Rational::from(4), "Credits");
+        let roman = RomanNumerals::default();
+        let answer_to = |q: &str| answer(&roman, &kb, parse_sentence(q).statement());
+        assert_eq!(
+            answer_to("how much Gold can I buy for 26 Credits ?"),
+            "26 Credits buy glob glob Gold, 2 Credits left"
+        );
+        assert_eq!(
+            answer_to("how many Gold is 11 Credits ?"),
+            "11 Credits buy 0 Gold, 11 Credits left"
+        );
+        assert_eq!(
+            answer_to("how many Zorkmids is 10 Credits ?"),
+            "10 Credits buy 2.5 Zorkmids"
+        );
+        assert_eq!(
+            answer_to("how many Silver is 10 Credits ?"),
+            "I don't know how to convert Credits to Silver"
+        );
+    }
+
+    #[test]
+    fn test_answer_literal_amounts() {
+        let mut nm: HashMap<String, String> = HashMap::new();
+        nm.insert("pish".to_string(), "X".to_string());
+        nm.insert("tegj".to_string(), "L".to_string());
+        let mut um: HashMap<String, Rational> = HashMap::new();
+        um.insert("Silver".to_string(), Rational::from(17));
+        let roman = RomanNumerals::default();
+        assert_eq!(
+            answer_how_many_credits(&roman, &nm, &um, "how many Credits is 3 Silver ?"),
+            "3 Silver is 51 Credits (read 3 as arabic number)"
+        );
+        assert_eq!(
+            answer_how_many_credits(&roman, &nm, &um, "how many Credits is XII Silver ?"),
+            "XII Silver is 204 Credits (read XII as roman numerals)"
+        );
+        assert_eq!(
+            answer_how_much(&roman, &nm, "how much is pish tegj II ?"),
+            "pish tegj II is 42 (read II as roman numerals)"
+        );
+        assert_eq!(
+            answer_how_much(&roman, &nm, "how much is 42 ?"),
+            "42 is 42 (read 42 as arabic number)"
+        );
+    }
 }
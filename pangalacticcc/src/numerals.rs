@@ -0,0 +1,539 @@
+use crate::rational::Rational;
+use crate::roman::{
+    apply_style, Notation, ParseMode, Roman, RomanFraction, Style, NULLA, SEMIS, UNCIA,
+};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Occurs when a sequence of symbols is not a valid number in a numeral system.
+/// Holds the reason and, if it can be attributed to a single symbol,
+/// the index of that symbol within the sequence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumeralError {
+    /// why the symbols could not be read
+    reason: String,
+    /// index of the offending symbol, if any
+    symbol: Option<usize>,
+}
+
+impl NumeralError {
+    /// Creates a new error, `symbol` is the index of the offending symbol if known
+    pub fn new(reason: String, symbol: Option<usize>) -> Self {
+        NumeralError { reason, symbol }
+    }
+    /// Returns the reason why the symbols could not be read
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+    /// Returns the index of the offending symbol, if any
+    pub fn symbol(&self) -> Option<usize> {
+        self.symbol
+    }
+}
+
+impl Error for NumeralError {}
+
+impl Display for NumeralError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.reason)
+    }
+}
+
+/// Result of reading a sequence of symbols
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    /// value of the number
+    value: Rational,
+    /// remark on how the symbols were interpreted, e.g. if they were not canonical
+    note: Option<String>,
+}
+
+impl Reading {
+    /// Creates a new reading of `value` with an optional remark on how it was interpreted
+    pub fn new(value: Rational, note: Option<String>) -> Self {
+        Reading { value, note }
+    }
+    /// Returns the value of the number
+    pub fn value(&self) -> Rational {
+        self.value
+    }
+    /// Returns the remark on how the symbols were interpreted, if any
+    pub fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+}
+
+/// A system of numerals alien words can stand for.
+/// Alien words are mapped to symbols of the system,
+/// sequences of alien words are read as sequences of those symbols.
+pub trait NumeralSystem {
+    /// Returns the name used to choose the system in input files, e.g. "roman"
+    /// for "numbers are roman"
+    fn name(&self) -> String;
+
+    /// Returns the symbol in canonical form if `s` is a symbol an alien word may stand for
+    /// (the right-hand side of e.g. "glob is I"), otherwise None
+    fn parse_symbol(&self, s: &str) -> Option<String>;
+
+    /// Returns the value of a sequence of symbols, as returned by parse_symbol,
+    /// or NumeralError if the sequence is not a valid number
+    fn read(&self, symbols: &[&str]) -> Result<Reading, NumeralError>;
+
+    /// Returns the symbols that write `value`, in the canonical form returned by parse_symbol,
+    /// or NumeralError if the value can not be written in this system
+    fn render(&self, value: Rational) -> Result<Vec<String>, NumeralError>;
+
+    /// Returns the symbols written as one number, e.g. for error messages
+    fn join(&self, symbols: &[&str]) -> String {
+        symbols.concat()
+    }
+
+    /// Returns the symbols that all need an alien numeral before numbers can be read,
+    /// e.g. the digits of a positional system
+    fn required_symbols(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Roman numerals, the default numeral system.
+/// Symbols are I,V,X,L,C,D,M, N for zero, the extended symbols for large values, e.g. V̅ or IↃↃ,
+/// and the fraction symbols S (one half) and · (one twelfth).
+/// Alien words may also stand for numerals of several symbols, e.g. IX,
+/// or for whole numbers written in arabic numerals, e.g. 1000 for M.
+/// Sequences of alien words are read as one numeral, so they are checked as a whole.
+/// # Example
+/// ```
+/// use pangalacticcc::numerals::{NumeralSystem, RomanNumerals};
+/// use pangalacticcc::rational::Rational;
+/// use pangalacticcc::roman::ParseMode;
+/// let roman = RomanNumerals::new(ParseMode::Strict);
+/// assert_eq!(roman.parse_symbol("I))"), Some("V\u{305}".to_string()));
+/// assert_eq!(roman.parse_symbol("1000"), Some("M".to_string()));
+/// assert_eq!(roman.read(&["XL", "II"]).unwrap().value(), Rational::from(42));
+/// assert!(roman.read(&["IX", "I"]).is_err());
+/// assert_eq!(roman.read(&["X", "L", "I", "I"]).unwrap().value(), Rational::from(42));
+/// assert!(roman.read(&["I", "I", "I", "I"]).is_err());
+/// assert_eq!(roman.read(&["I", "S"]).unwrap().value(), Rational::new(3, 2));
+/// let lenient = RomanNumerals::new(ParseMode::Lenient);
+/// assert_eq!(lenient.read(&["I", "I", "I", "I"]).unwrap().note(), Some("read IIII as IV"));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RomanNumerals {
+    /// how strictly numerals are checked
+    mode: ParseMode,
+    /// glyphs used when showing numerals
+    style: Style,
+}
+
+impl RomanNumerals {
+    /// Creates Roman numerals that are parsed in the given mode
+    pub fn new(mode: ParseMode) -> Self {
+        RomanNumerals {
+            mode,
+            style: Style::Ascii,
+        }
+    }
+    /// Returns the same Roman numerals, shown in the given style
+    /// # Example
+    /// ```
+    /// use pangalacticcc::numerals::{NumeralSystem, RomanNumerals};
+    /// use pangalacticcc::roman::{ParseMode, Style};
+    /// let lenient = RomanNumerals::new(ParseMode::Lenient).with_style(Style::Lowercase);
+    /// assert_eq!(lenient.read(&["I", "I", "X"]).unwrap().note(), Some("read iix as viii"));
+    /// assert_eq!(lenient.join(&["X", "L"]), "xl");
+    /// ```
+    pub fn with_style(self, style: Style) -> Self {
+        RomanNumerals { style, ..self }
+    }
+}
+
+impl NumeralSystem for RomanNumerals {
+    fn name(&self) -> String {
+        "roman".to_string()
+    }
+
+    fn parse_symbol(&self, s: &str) -> Option<String> {
+        // a whole number in arabic numerals, e.g. 1000
+        if !s.is_empty() && s.chars().all(|c| c.is_ascii_digit()) {
+            let roman = Roman::try_from(s.parse::<u32>().ok()?).ok()?;
+            return Some(roman.to_notation(Notation::Vinculum));
+        }
+        // a single symbol or a numeral of several symbols, e.g. IX
+        let roman = RomanFraction::parse_with_mode(s, self.mode).ok()?;
+        Some(roman.render(Notation::Vinculum, Style::Ascii))
+    }
+
+    fn read(&self, symbols: &[&str]) -> Result<Reading, NumeralError> {
+        let numeral = symbols.concat();
+        match RomanFraction::parse_with_mode(&numeral, self.mode) {
+            Ok(roman) => {
+                // only happens in lenient mode
+                let note = if roman.get_representation() != numeral {
+                    Some(format!(
+                        "read {} as {}",
+                        self.join(symbols),
+                        roman.render(Notation::Vinculum, self.style)
+                    ))
+                } else {
+                    None
+                };
+                Ok(Reading::new(roman.to_rational(), note))
+            }
+            Err(e) => {
+                // find the symbol that starts at or spans the offending character
+                let mut symbol = None;
+                if let Some(position) = e.position() {
+                    let mut end = 0;
+                    for (i, s) in symbols.iter().enumerate() {
+                        end += s.chars().count();
+                        if position < end {
+                            symbol = Some(i);
+                            break;
+                        }
+                    }
+                }
+                Err(NumeralError::new(e.rule().to_string(), symbol))
+            }
+        }
+    }
+
+    fn render(&self, value: Rational) -> Result<Vec<String>, NumeralError> {
+        let error = || {
+            NumeralError::new(
+                format!("{} can not be written in roman numerals", value),
+                None,
+            )
+        };
+        // fractions can only be written in twelfths
+        if 12 % value.denom() != 0 || value < Rational::from(0) {
+            return Err(error());
+        }
+        let whole = value.numer() / value.denom();
+        let twelfths = (value - Rational::from(whole)).numer() * 12 / value.denom();
+        let mut symbols = match whole {
+            // zero is N, fractions below one do not have a whole part
+            0 if twelfths == 0 => vec![NULLA.to_string()],
+            0 => Vec::new(),
+            _ => Roman::try_from(whole).map_err(|_| error())?.symbols(),
+        };
+        if twelfths >= 6 {
+            symbols.push(SEMIS.to_string());
+        }
+        for _ in 0..twelfths % 6 {
+            symbols.push(UNCIA.to_string());
+        }
+        Ok(symbols)
+    }
+
+    fn join(&self, symbols: &[&str]) -> String {
+        apply_style(&symbols.concat(), self.style)
+    }
+}
+
+/// Positional numerals in base 2 to 36, declared e.g. by "numbers are base 7".
+/// Symbols are the digits written as decimal numbers, e.g. 0 to 6 for base 7,
+/// the most significant digit comes first.
+/// # Example
+/// ```
+/// use pangalacticcc::numerals::{NumeralSystem, PositionalNumerals};
+/// use pangalacticcc::rational::Rational;
+/// let base7 = PositionalNumerals::new(7).unwrap();
+/// assert_eq!(base7.name(), "base 7");
+/// assert_eq!(base7.parse_symbol("6"), Some("6".to_string()));
+/// assert_eq!(base7.parse_symbol("7"), None);
+/// assert_eq!(base7.read(&["1", "0", "3"]).unwrap().value(), Rational::from(52));
+/// assert_eq!(base7.render(Rational::from(52)).unwrap(), vec!["1", "0", "3"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PositionalNumerals {
+    /// number of digits
+    base: u32,
+}
+
+impl PositionalNumerals {
+    /// Creates positional numerals with the given base,
+    /// returns None if the base is not within 2 to 36
+    pub fn new(base: u32) -> Option<Self> {
+        if (2..=36).contains(&base) {
+            Some(PositionalNumerals { base })
+        } else {
+            None
+        }
+    }
+    /// Returns the number of digits
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+}
+
+impl NumeralSystem for PositionalNumerals {
+    fn name(&self) -> String {
+        format!("base {}", self.base)
+    }
+
+    fn parse_symbol(&self, s: &str) -> Option<String> {
+        if !s.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        match s.parse::<u32>() {
+            Ok(digit) if digit < self.base => Some(digit.to_string()),
+            _ => None,
+        }
+    }
+
+    fn read(&self, symbols: &[&str]) -> Result<Reading, NumeralError> {
+        if symbols.is_empty() {
+            return Err(NumeralError::new("no digits given".to_string(), None));
+        }
+        let mut value: i128 = 0;
+        for (i, symbol) in symbols.iter().enumerate() {
+            let digit = match self.parse_symbol(symbol) {
+                Some(d) => d.parse::<i128>().unwrap_or_default(),
+                None => {
+                    return Err(NumeralError::new(
+                        format!("{} is not a digit of {} numbers", symbol, self.name()),
+                        Some(i),
+                    ))
+                }
+            };
+            value = match value
+                .checked_mul(self.base as i128)
+                .and_then(|v| v.checked_add(digit))
+            {
+                Some(v) => v,
+                None => return Err(NumeralError::new("value too large".to_string(), Some(i))),
+            };
+        }
+        Ok(Reading::new(Rational::from(value), None))
+    }
+
+    fn render(&self, value: Rational) -> Result<Vec<String>, NumeralError> {
+        if !value.is_integer() || value.numer() < 0 {
+            return Err(NumeralError::new(
+                format!("{} can not be written in {} numerals", value, self.name()),
+                None,
+            ));
+        }
+        let mut rest = value.numer();
+        let mut digits = vec![(rest % self.base as i128).to_string()];
+        rest /= self.base as i128;
+        while rest > 0 {
+            digits.push((rest % self.base as i128).to_string());
+            rest /= self.base as i128;
+        }
+        digits.reverse();
+        Ok(digits)
+    }
+
+    fn join(&self, symbols: &[&str]) -> String {
+        // digits may have more than one character
+        symbols.join(" ")
+    }
+
+    fn required_symbols(&self) -> Vec<String> {
+        (0..self.base).map(|d| d.to_string()).collect()
+    }
+}
+
+/// Returns the numeral system called `name` in input files, or None if there is no such system
+/// # Arguments
+/// * `name` - name of the system, case is ignored, e.g. "roman" or "base 7"
+/// * `roman` - Roman numerals as configured on the command line, returned for "roman"
+/// # Example
+/// ```
+/// use pangalacticcc::numerals::{numeral_system_from_name, RomanNumerals};
+/// let roman = RomanNumerals::default();
+/// assert_eq!(numeral_system_from_name("Roman", roman).unwrap().name(), "roman");
+/// assert_eq!(numeral_system_from_name("base 7", roman).unwrap().name(), "base 7");
+/// assert!(numeral_system_from_name("base 1", roman).is_none());
+/// assert!(numeral_system_from_name("klingon", roman).is_none());
+/// ```
+pub fn numeral_system_from_name(
+    name: &str,
+    roman: RomanNumerals,
+) -> Option<Box<dyn NumeralSystem>> {
+    let name = name.to_lowercase();
+    if let Some(base) = name.strip_prefix("base ") {
+        return match base
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .and_then(PositionalNumerals::new)
+        {
+            Some(system) => Some(Box::new(system)),
+            None => None,
+        };
+    }
+    match name.as_str() {
+        "roman" => Some(Box::new(roman)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roman_numerals_parse_symbol() {
+        let roman = RomanNumerals::default();
+        assert_eq!(roman.parse_symbol("X"), Some("X".to_string()));
+        assert_eq!(roman.parse_symbol("CI)"), Some("M".to_string()));
+        assert_eq!(roman.parse_symbol("5"), Some("V".to_string()));
+        assert_eq!(roman.parse_symbol("IIX"), None);
+        assert_eq!(roman.parse_symbol("5X"), None);
+        assert_eq!(roman.parse_symbol("-5"), None);
+        assert_eq!(roman.parse_symbol("4000000"), None);
+    }
+
+    #[test]
+    fn test_roman_numerals_parse_compound_symbol() {
+        let roman = RomanNumerals::default();
+        assert_eq!(roman.parse_symbol("IX"), Some("IX".to_string()));
+        assert_eq!(roman.parse_symbol("xl"), Some("XL".to_string()));
+        assert_eq!(roman.parse_symbol("IS\u{B7}"), Some("IS\u{B7}".to_string()));
+        assert_eq!(roman.parse_symbol("0"), Some("N".to_string()));
+        assert_eq!(roman.parse_symbol("4000"), Some("MV\u{305}".to_string()));
+        let lenient = RomanNumerals::new(ParseMode::Lenient);
+        assert_eq!(lenient.parse_symbol("IIX"), Some("VIII".to_string()));
+    }
+
+    #[test]
+    fn test_roman_numerals_read_compound() {
+        let roman = RomanNumerals::default();
+        assert_eq!(
+            roman.read(&["M", "CM", "III"]).unwrap().value(),
+            Rational::from(1903)
+        );
+        let e = roman.read(&["X", "IX", "I"]).unwrap_err();
+        assert_eq!(
+            e.reason(),
+            "symbols are not in canonical order, expected XX"
+        );
+        let e = roman.read(&["XL", "L"]).unwrap_err();
+        assert_eq!(e.reason(), "L may never be repeated");
+        assert_eq!(e.symbol(), Some(1));
+    }
+
+    #[test]
+    fn test_roman_numerals_too_long() {
+        // repeated subtractive pairs, e.g. alien numerals for C̅ and M̅ used over and over
+        let pairs = ["C\u{305}", "M\u{305}"].repeat(2400);
+        for roman in [
+            RomanNumerals::default(),
+            RomanNumerals::new(ParseMode::Lenient),
+        ] {
+            let e = roman.read(&pairs).unwrap_err();
+            assert_eq!(e.reason(), "value out of range");
+            assert_eq!(roman.parse_symbol(&pairs.concat()), None);
+        }
+    }
+
+    #[test]
+    fn test_roman_numerals_read() {
+        let roman = RomanNumerals::default();
+        let reading = roman.read(&["M", "C", "M", "I", "I", "I"]).unwrap();
+        assert_eq!(reading.value(), Rational::from(1903));
+        assert_eq!(reading.note(), None);
+    }
+
+    #[test]
+    fn test_roman_numerals_read_error_points_to_symbol() {
+        let roman = RomanNumerals::default();
+        let e = roman.read(&["I", "V\u{305}", "V"]).unwrap_err();
+        assert_eq!(e.symbol(), Some(0));
+        let e = roman.read(&["X", "V\u{305}", "V\u{305}"]).unwrap_err();
+        assert_eq!(e.reason(), "X may not be subtracted from V\u{305}");
+        assert_eq!(e.symbol(), Some(0));
+        let e = roman.read(&["M", "V\u{305}", "V\u{305}"]).unwrap_err();
+        assert_eq!(e.reason(), "V\u{305} may never be repeated");
+        assert_eq!(e.symbol(), Some(2));
+    }
+
+    #[test]
+    fn test_roman_numerals_render() {
+        let roman = RomanNumerals::default();
+        assert_eq!(
+            roman.render(Rational::from(42)).unwrap(),
+            vec!["X", "L", "I", "I"]
+        );
+        assert_eq!(roman.render(Rational::from(0)).unwrap(), vec!["N"]);
+        assert!(roman.render(Rational::from(-1)).is_err());
+        assert!(roman.render(Rational::new(1, 5)).is_err());
+    }
+
+    #[test]
+    fn test_roman_numerals_fractions() {
+        let roman = RomanNumerals::default();
+        assert_eq!(roman.parse_symbol("S"), Some("S".to_string()));
+        assert_eq!(roman.parse_symbol("."), Some("\u{B7}".to_string()));
+        let reading = roman.read(&["V", "S", "\u{B7}"]).unwrap();
+        assert_eq!(reading.value(), Rational::new(67, 12));
+        assert_eq!(
+            roman.render(Rational::new(67, 12)).unwrap(),
+            vec!["V", "S", "\u{B7}"]
+        );
+        assert_eq!(
+            roman.render(Rational::new(1, 3)).unwrap(),
+            vec!["\u{B7}"; 4]
+        );
+        let e = roman.read(&["\u{B7}", "S"]).unwrap_err();
+        assert_eq!(e.reason(), "S must precede the twelfths");
+        assert_eq!(e.symbol(), Some(1));
+    }
+
+    #[test]
+    fn test_positional_numerals_read() {
+        let base7 = PositionalNumerals::new(7).unwrap();
+        assert_eq!(base7.read(&["0"]).unwrap().value(), Rational::from(0));
+        assert_eq!(base7.read(&["6", "6"]).unwrap().value(), Rational::from(48));
+        let e = base7.read(&["1", "7"]).unwrap_err();
+        assert_eq!(e.symbol(), Some(1));
+        assert!(base7.read(&[]).is_err());
+    }
+
+    #[test]
+    fn test_positional_numerals_read_too_large() {
+        let base2 = PositionalNumerals::new(2).unwrap();
+        let digits = vec!["1"; 64];
+        assert_eq!(
+            base2.read(&digits).unwrap().value(),
+            Rational::new(u64::MAX as i128, 1)
+        );
+        let digits = vec!["1"; 128];
+        assert_eq!(base2.read(&digits).unwrap_err().symbol(), Some(127));
+    }
+
+    #[test]
+    fn test_positional_numerals_round_trip() {
+        let base16 = PositionalNumerals::new(16).unwrap();
+        for value in [0, 1, 15, 16, 255, 4096, 57800] {
+            let digits = base16.render(Rational::from(value)).unwrap();
+            let digits = digits.iter().map(|d| d.as_str()).collect::<Vec<_>>();
+            assert_eq!(base16.read(&digits).unwrap().value(), Rational::from(value));
+        }
+        assert!(base16.render(Rational::from(-1)).is_err());
+    }
+
+    #[test]
+    fn test_positional_numerals_base_range() {
+        assert!(PositionalNumerals::new(1).is_none());
+        assert!(PositionalNumerals::new(37).is_none());
+        assert_eq!(
+            PositionalNumerals::new(36)
+                .unwrap()
+                .required_symbols()
+                .len(),
+            36
+        );
+    }
+
+    #[test]
+    fn test_roman_numerals_read_lenient() {
+        let roman = RomanNumerals::new(ParseMode::Lenient);
+        let reading = roman.read(&["X", "I", "I", "X"]).unwrap();
+        assert_eq!(reading.value(), Rational::from(18));
+        assert_eq!(reading.note(), Some("read XIIX as XVIII"));
+    }
+}
@@ -0,0 +1,908 @@
+//! Tokenizer and parser for the sentences of an input file.
+//! Every line is split into tokens with byte spans and parsed into a typed Statement,
+//! so the language is defined in one place.
+use crate::rational::{ParseRationalError, Rational};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+/// Occurs when a double quote is opened but never closed
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnterminatedQuoteError {
+    /// byte offset of the opening quote
+    position: usize,
+}
+
+impl UnterminatedQuoteError {
+    /// Returns the byte offset of the opening quote
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl Error for UnterminatedQuoteError {}
+
+impl Display for UnterminatedQuoteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "quote opened at byte {} is never closed", self.position)
+    }
+}
+
+/// Byte range `start..end` of a token or sentence within the parsed text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// byte offset of the first byte
+    start: usize,
+    /// byte offset after the last byte
+    end: usize,
+}
+
+impl Span {
+    /// Creates a new span covering the bytes `start..end`
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+    /// Returns the byte offset of the first byte
+    pub fn start(&self) -> usize {
+        self.start
+    }
+    /// Returns the byte offset after the last byte
+    pub fn end(&self) -> usize {
+        self.end
+    }
+    /// Returns the span as range, e.g. to slice the parsed text
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+    /// Returns the span moved `offset` bytes to the right
+    fn shift(self, offset: usize) -> Self {
+        Span::new(self.start + offset, self.end + offset)
+    }
+}
+
+/// The kind of a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// a run of characters up to the next whitespace, `?` or `"`
+    Word,
+    /// a word that is part of the sentence structure, e.g. "is" or "how"
+    Keyword,
+    /// text in double quotes, e.g. a unit name with spaces
+    Quoted,
+    /// `?` at the end of a question
+    QuestionMark,
+    /// `#` and the rest of the line, e.g. "# prices from Vogon market"
+    Comment,
+}
+
+/// A token of a sentence
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    kind: TokenKind,
+    /// text of the token, without the quotes for Quoted tokens
+    /// and without `#` and surrounding whitespace for Comment tokens
+    text: String,
+    /// position of the token, including the quotes for Quoted tokens and `#` for Comment tokens
+    span: Span,
+}
+
+impl Token {
+    /// Creates a new token
+    pub fn new(kind: TokenKind, text: &str, span: Span) -> Self {
+        Token {
+            kind,
+            text: text.to_string(),
+            span,
+        }
+    }
+    /// Returns the kind of the token
+    pub fn kind(&self) -> TokenKind {
+        self.kind
+    }
+    /// Returns the text of the token, without the quotes for Quoted tokens
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    /// Returns the position of the token
+    pub fn span(&self) -> Span {
+        self.span
+    }
+    /// Returns true if the token is the word `keyword`, ignoring case
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.kind != TokenKind::Quoted && self.text.eq_ignore_ascii_case(keyword)
+    }
+}
+
+/// A sentence of the input, classified by its structure.
+/// Keywords are left out, their tokens are kept in the Sentence.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    /// numbers are <name>, e.g. "numbers are base 7"
+    NumeralSystem { name: Vec<Token> },
+    /// <numeral> is <symbol>, e.g. "glob is I"
+    Numeral { numeral: Token, symbol: Token },
+    /// <amount> <unit> is <value> <currency>, e.g. "glob glob Silver is 34 Credits",
+    /// the value is a decimal number, e.g. 17.5 or 57,800.
+    /// Where the amount ends and the unit starts depends on the known alien numerals,
+    /// unless the unit is quoted.
+    Unit {
+        words: Vec<Token>,
+        value: Token,
+        currency: Token,
+    },
+    /// <amount> <currency> is <value> <target>, e.g. "1 Zorkmid is 4 Credits",
+    /// amount and value in arabic numerals
+    ExchangeRate {
+        amount: Token,
+        currency: Token,
+        value: Token,
+        target: Token,
+    },
+    /// how much is <amount> ?
+    HowMuch { amount: Vec<Token> },
+    /// how many <target> is <amount> <unit> ?, where the target is a currency or another unit,
+    /// e.g. "how many Credits is glob Silver ?" or "how many Silver is glob Gold ?".
+    /// The target consists of plain words or is quoted.
+    HowMany {
+        target: Vec<Token>,
+        words: Vec<Token>,
+    },
+    /// how much <target> can I buy for <value> <currency> ?, or how many <target> is <value> <currency> ?,
+    /// e.g. "how much Silver can I buy for 100 Credits ?", value is a decimal number.
+    /// The target is a unit or another currency, it consists of plain words or is quoted.
+    Purchase {
+        target: Vec<Token>,
+        value: Token,
+        currency: Token,
+    },
+    /// how do I say <number> ?, or what is <number> in alien ?,
+    /// asks for a whole number in arabic numerals to be written in alien numerals
+    HowToSay { number: Token },
+    /// include <path>, e.g. include "numerals.txt", reads the lines of another file,
+    /// the path is quoted and relative to the including file
+    Include { path: Token },
+    /// a line with nothing but a comment, see Sentence::comment
+    Comment,
+    /// anything that does not fit the other structures
+    Unknown,
+}
+
+/// A parsed line of the input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sentence {
+    /// the line as written, without the comment and surrounding whitespace
+    text: String,
+    /// position of the text
+    span: Span,
+    /// every token of the line, except the comment
+    tokens: Vec<Token>,
+    /// the comment at the end of the line, if any
+    comment: Option<Token>,
+    statement: Statement,
+}
+
+impl Sentence {
+    /// Returns the line as written, without the comment and surrounding whitespace
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    /// Returns the position of the text
+    pub fn span(&self) -> Span {
+        self.span
+    }
+    /// Returns the comment at the end of the line, if any.
+    /// Comments are not part of the statement.
+    /// # Example
+    /// ```
+    /// use pangalacticcc::parser::{parse_sentence, Statement};
+    /// let sentence = parse_sentence("glob is I # one");
+    /// assert_eq!(sentence.text(), "glob is I");
+    /// assert_eq!(sentence.comment().unwrap().text(), "one");
+    /// assert!(matches!(sentence.statement(), Statement::Numeral { .. }));
+    /// let sentence = parse_sentence("# prices from Vogon market, 2026");
+    /// assert_eq!(sentence.statement(), &Statement::Comment);
+    /// ```
+    pub fn comment(&self) -> Option<&Token> {
+        self.comment.as_ref()
+    }
+    /// Returns every token of the line, including keywords, except the comment.
+    /// Empty if the line could not be tokenized.
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+    /// Returns what the sentence states or asks
+    pub fn statement(&self) -> &Statement {
+        &self.statement
+    }
+}
+
+/// Splits a line into tokens. Tokens are separated by any amount of whitespace,
+/// `?` is a token of its own and text in double quotes is a single token.
+/// `#` outside of double quotes starts a comment, which is the last token.
+/// Returns UnterminatedQuoteError if a quote is not closed.
+/// # Example
+/// ```
+/// use pangalacticcc::parser::{tokenize, TokenKind};
+/// let tokens = tokenize("how much is  glob?").unwrap();
+/// let texts = tokens.iter().map(|t| t.text()).collect::<Vec<_>>();
+/// assert_eq!(texts, vec!["how", "much", "is", "glob", "?"]);
+/// assert_eq!(tokens[3].span().range(), 13..17);
+/// let tokens = tokenize("glob \"Dark Matter\"").unwrap();
+/// assert_eq!(tokens[1].kind(), TokenKind::Quoted);
+/// assert_eq!(tokens[1].text(), "Dark Matter");
+/// assert!(tokenize("glob \"Dark Matter").is_err());
+/// ```
+pub fn tokenize(line: &str) -> Result<Vec<Token>, UnterminatedQuoteError> {
+    let mut tokens = Vec::new();
+    let mut chars = line.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        if c == '?' {
+            tokens.push(Token::new(
+                TokenKind::QuestionMark,
+                "?",
+                Span::new(start, start + 1),
+            ));
+            continue;
+        }
+        if c == '#' {
+            let end = line.trim_end().len();
+            tokens.push(Token::new(
+                TokenKind::Comment,
+                line[start + 1..end].trim(),
+                Span::new(start, end),
+            ));
+            break;
+        }
+        if c == '"' {
+            let len = match line[start + 1..].find('"') {
+                Some(len) => len,
+                None => return Err(UnterminatedQuoteError { position: start }),
+            };
+            let end = start + len + 2;
+            while chars.next_if(|&(i, _)| i < end).is_some() {}
+            tokens.push(Token::new(
+                TokenKind::Quoted,
+                &line[start + 1..end - 1],
+                Span::new(start, end),
+            ));
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        while let Some((i, c)) =
+            chars.next_if(|&(_, c)| !c.is_whitespace() && c != '?' && c != '"' && c != '#')
+        {
+            end = i + c.len_utf8();
+        }
+        tokens.push(Token::new(
+            TokenKind::Word,
+            &line[start..end],
+            Span::new(start, end),
+        ));
+    }
+    Ok(tokens)
+}
+
+/// Parses a single line, spans are byte offsets within `line`.
+/// Keywords are matched case-insensitively.
+/// # Example
+/// ```
+/// use pangalacticcc::parser::{parse_sentence, Statement};
+/// let sentence = parse_sentence("glob is I");
+/// match sentence.statement() {
+///     Statement::Numeral { numeral, symbol } => {
+///         assert_eq!(numeral.text(), "glob");
+///         assert_eq!(symbol.text(), "I");
+///         assert_eq!(symbol.span().range(), 8..9);
+///     }
+///     _ => panic!("not a numeral statement"),
+/// }
+/// assert!(matches!(parse_sentence("How much is glob ?").statement(), Statement::HowMuch { .. }));
+/// assert!(matches!(parse_sentence("how much wood ?").statement(), Statement::Unknown));
+/// assert!(matches!(parse_sentence("how much is ?").statement(), Statement::Unknown));
+/// ```
+pub fn parse_sentence(line: &str) -> Sentence {
+    parse_line(line, 0)
+}
+
+/// Parses every non-empty line of `input`, spans are byte offsets within `input`.
+/// # Example
+/// ```
+/// use pangalacticcc::parser::{parse, Statement};
+/// let input = "glob is I\n\nglob Silver is 17 Credits\n";
+/// let sentences = parse(input);
+/// assert_eq!(sentences.len(), 2);
+/// assert_eq!(&input[sentences[1].span().range()], "glob Silver is 17 Credits");
+/// assert!(matches!(sentences[1].statement(), Statement::Unit { .. }));
+/// ```
+pub fn parse(input: &str) -> Vec<Sentence> {
+    let mut sentences = Vec::new();
+    let mut offset = 0;
+    for line in input.split('\n') {
+        // skip empty
+        if !line.trim().is_empty() {
+            sentences.push(parse_line(line, offset));
+        }
+        offset += line.len() + 1;
+    }
+    sentences
+}
+
+/// Parses `line`, which starts at byte `offset` of the parsed text.
+/// A comment is stripped before the statement is classified.
+fn parse_line(line: &str, offset: usize) -> Sentence {
+    let (tokens, comment, statement) = match tokenize(line) {
+        Ok(mut tokens) => {
+            for token in tokens.iter_mut() {
+                token.span = token.span.shift(offset);
+            }
+            let comment = match tokens.last() {
+                Some(last) if last.kind == TokenKind::Comment => tokens.pop(),
+                _ => None,
+            };
+            let statement = if tokens.is_empty() && comment.is_some() {
+                Statement::Comment
+            } else {
+                parse_tokens(&mut tokens)
+            };
+            (tokens, comment, statement)
+        }
+        Err(_) => (Vec::new(), None, Statement::Unknown),
+    };
+    let without_comment = match &comment {
+        Some(comment) => &line[..comment.span.start - offset],
+        None => line,
+    };
+    let text = without_comment.trim();
+    let start = offset + without_comment.len() - without_comment.trim_start().len();
+    Sentence {
+        text: text.to_string(),
+        span: Span::new(start, start + text.len()),
+        tokens,
+        comment,
+        statement,
+    }
+}
+
+/// Returns true if `tokens` start with the words `keywords`
+fn starts_with_keywords(tokens: &[Token], keywords: &[&str]) -> bool {
+    tokens.len() >= keywords.len()
+        && tokens
+            .iter()
+            .zip(keywords)
+            .all(|(token, keyword)| token.is_keyword(keyword))
+}
+
+/// Returns true if the word only consists of letters, digits and underscores
+fn is_plain_word(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Returns true if the token is a whole number in arabic numerals
+fn is_number(token: &Token) -> bool {
+    token.kind == TokenKind::Word && token.text.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Returns true if the token is a decimal number in arabic numerals,
+/// e.g. "17.5" or "57,800", see Rational::from_str.
+/// Numbers too large to calculate with are still numbers, so they can be reported.
+fn is_decimal(token: &Token) -> bool {
+    token.kind == TokenKind::Word
+        && token.text.starts_with(|c: char| c.is_ascii_digit())
+        && token.text.parse::<Rational>() != Err(ParseRationalError::Invalid)
+}
+
+/// Returns true if the token is a plain word, e.g. a currency
+fn is_plain_word_token(token: &Token) -> bool {
+    token.kind == TokenKind::Word && is_plain_word(&token.text)
+}
+
+/// Returns true if the tokens are a valid target of a "how many" question:
+/// plain words or a single quoted unit
+fn is_target(tokens: &[Token]) -> bool {
+    match tokens {
+        [quoted] if quoted.kind == TokenKind::Quoted => {
+            quoted.text.split_whitespace().all(is_plain_word)
+        }
+        _ => !tokens.is_empty() && tokens.iter().all(is_plain_word_token),
+    }
+}
+
+/// Returns true if the tokens are valid amount and unit of a statement about units:
+/// at least two plain words, the last one may be quoted instead
+fn is_amount_and_unit(tokens: &[Token]) -> bool {
+    match tokens.split_last() {
+        Some((last, amount)) if !amount.is_empty() => {
+            let last_valid = match last.kind {
+                TokenKind::Word => is_plain_word(&last.text),
+                TokenKind::Quoted => last.text.split_whitespace().all(is_plain_word),
+                _ => false,
+            };
+            last_valid && amount.iter().all(is_plain_word_token)
+        }
+        _ => false,
+    }
+}
+
+/// Classifies the tokens of a sentence and marks the keywords found
+fn parse_tokens(tokens: &mut [Token]) -> Statement {
+    let n = tokens.len();
+    let is_question = n > 0 && tokens[n - 1].kind == TokenKind::QuestionMark;
+    let mark_keywords = |tokens: &mut [Token]| {
+        for token in tokens.iter_mut() {
+            token.kind = TokenKind::Keyword;
+        }
+    };
+
+    if n > 2 && starts_with_keywords(tokens, &["numbers", "are"]) {
+        mark_keywords(&mut tokens[..2]);
+        return Statement::NumeralSystem {
+            name: tokens[2..].to_vec(),
+        };
+    }
+    if n == 2 && tokens[0].is_keyword("include") && tokens[1].kind == TokenKind::Quoted {
+        mark_keywords(&mut tokens[..1]);
+        return Statement::Include {
+            path: tokens[1].clone(),
+        };
+    }
+    if n == 3
+        && is_plain_word_token(&tokens[0])
+        && tokens[1].is_keyword("is")
+        && tokens[2].kind == TokenKind::Word
+    {
+        mark_keywords(&mut tokens[1..2]);
+        return Statement::Numeral {
+            numeral: tokens[0].clone(),
+            symbol: tokens[2].clone(),
+        };
+    }
+    // an amount in arabic numerals makes it an exchange rate
+    if n == 5
+        && is_number(&tokens[0])
+        && is_plain_word_token(&tokens[1])
+        && tokens[2].is_keyword("is")
+        && is_number(&tokens[3])
+        && is_plain_word_token(&tokens[4])
+    {
+        mark_keywords(&mut tokens[2..3]);
+        return Statement::ExchangeRate {
+            amount: tokens[0].clone(),
+            currency: tokens[1].clone(),
+            value: tokens[3].clone(),
+            target: tokens[4].clone(),
+        };
+    }
+    if n >= 5
+        && tokens[n - 3].is_keyword("is")
+        && is_decimal(&tokens[n - 2])
+        && is_plain_word_token(&tokens[n - 1])
+        && is_amount_and_unit(&tokens[..n - 3])
+    {
+        mark_keywords(&mut tokens[n - 3..n - 2]);
+        return Statement::Unit {
+            words: tokens[..n - 3].to_vec(),
+            value: tokens[n - 2].clone(),
+            currency: tokens[n - 1].clone(),
+        };
+    }
+    // money instead of an amount of a unit makes it a purchase
+    let is_money = |tokens: &[Token]| {
+        tokens.len() == 2 && is_decimal(&tokens[0]) && is_plain_word_token(&tokens[1])
+    };
+    if is_question
+        && n > 9
+        && starts_with_keywords(tokens, &["how", "much"])
+        && starts_with_keywords(&tokens[n - 7..], &["can", "i", "buy", "for"])
+        && is_target(&tokens[2..n - 7])
+        && is_money(&tokens[n - 3..n - 1])
+    {
+        mark_keywords(&mut tokens[..2]);
+        mark_keywords(&mut tokens[n - 7..n - 3]);
+        return Statement::Purchase {
+            target: tokens[2..n - 7].to_vec(),
+            value: tokens[n - 3].clone(),
+            currency: tokens[n - 2].clone(),
+        };
+    }
+    if is_question
+        && n > 6
+        && starts_with_keywords(tokens, &["how", "many"])
+        && tokens[n - 4].is_keyword("is")
+        && is_target(&tokens[2..n - 4])
+        && is_money(&tokens[n - 3..n - 1])
+    {
+        mark_keywords(&mut tokens[..2]);
+        mark_keywords(&mut tokens[n - 4..n - 3]);
+        return Statement::Purchase {
+            target: tokens[2..n - 4].to_vec(),
+            value: tokens[n - 3].clone(),
+            currency: tokens[n - 2].clone(),
+        };
+    }
+    if is_question && starts_with_keywords(tokens, &["how", "many"]) {
+        // the target ends at the first "is"
+        if let Some(is) = (3..n - 1).find(|i| tokens[*i].is_keyword("is")) {
+            if is_target(&tokens[2..is]) {
+                mark_keywords(&mut tokens[..2]);
+                mark_keywords(&mut tokens[is..is + 1]);
+                return Statement::HowMany {
+                    target: tokens[2..is].to_vec(),
+                    words: tokens[is + 1..n - 1].to_vec(),
+                };
+            }
+        }
+    }
+    if is_question
+        && n == 6
+        && starts_with_keywords(tokens, &["how", "do", "i", "say"])
+        && is_number(&tokens[4])
+    {
+        mark_keywords(&mut tokens[..4]);
+        return Statement::HowToSay {
+            number: tokens[4].clone(),
+        };
+    }
+    if is_question
+        && n == 6
+        && starts_with_keywords(tokens, &["what", "is"])
+        && is_number(&tokens[2])
+        && starts_with_keywords(&tokens[3..], &["in", "alien"])
+    {
+        mark_keywords(&mut tokens[..2]);
+        mark_keywords(&mut tokens[3..5]);
+        return Statement::HowToSay {
+            number: tokens[2].clone(),
+        };
+    }
+    if is_question && n > 4 && starts_with_keywords(tokens, &["how", "much", "is"]) {
+        mark_keywords(&mut tokens[..3]);
+        return Statement::HowMuch {
+            amount: tokens[3..n - 1].to_vec(),
+        };
+    }
+    Statement::Unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(tokens: &[Token]) -> Vec<&str> {
+        tokens.iter().map(|t| t.text()).collect()
+    }
+
+    #[test]
+    fn test_tokenize_spans() {
+        let line = "  glob\tprok  Silver ?";
+        let tokens = tokenize(line).unwrap();
+        assert_eq!(texts(&tokens), vec!["glob", "prok", "Silver", "?"]);
+        for token in &tokens {
+            assert_eq!(&line[token.span().range()], token.text());
+        }
+        assert_eq!(tokens[3].kind(), TokenKind::QuestionMark);
+    }
+
+    #[test]
+    fn test_tokenize_quoted_span_includes_quotes() {
+        let line = "pish \"prok  Dust\"?";
+        let tokens = tokenize(line).unwrap();
+        assert_eq!(texts(&tokens), vec!["pish", "prok  Dust", "?"]);
+        assert_eq!(&line[tokens[1].span().range()], "\"prok  Dust\"");
+    }
+
+    #[test]
+    fn test_tokenize_non_ascii() {
+        let tokens = tokenize("五 is V\u{305}").unwrap();
+        assert_eq!(texts(&tokens), vec!["五", "is", "V\u{305}"]);
+        assert_eq!(tokens[1].span(), Span::new(4, 6));
+    }
+
+    #[test]
+    fn test_tokenize_unterminated_quote() {
+        let err = tokenize("glob \"prok Dust is 10 Credits").unwrap_err();
+        assert_eq!(err.position(), 5);
+    }
+
+    #[test]
+    fn test_parse_full_example() {
+        let input = "glob is I\n\
+            prok is V\n\
+            glob glob Silver is 34 Credits\n\
+            how much is glob prok ?\n\
+            how many Credits is glob prok Silver ?\n\
+            \n\
+            how much wood could a woodchuck chuck if a woodchuck could chuck wood ?\n\
+            numbers are roman";
+        let sentences = parse(input);
+        assert_eq!(sentences.len(), 7);
+        assert!(matches!(
+            sentences[0].statement(),
+            Statement::Numeral { .. }
+        ));
+        assert!(matches!(sentences[2].statement(), Statement::Unit { .. }));
+        assert!(matches!(
+            sentences[3].statement(),
+            Statement::HowMuch { .. }
+        ));
+        assert!(matches!(
+            sentences[4].statement(),
+            Statement::HowMany { .. }
+        ));
+        assert_eq!(sentences[5].statement(), &Statement::Unknown);
+        assert!(matches!(
+            sentences[6].statement(),
+            Statement::NumeralSystem { .. }
+        ));
+        for sentence in &sentences {
+            assert_eq!(&input[sentence.span().range()], sentence.text());
+            for token in sentence.tokens() {
+                if token.kind() != TokenKind::Quoted {
+                    assert_eq!(&input[token.span().range()], token.text());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_marks_keywords() {
+        let sentence = parse_sentence("How many Zorkmids IS glob Silver?");
+        let kinds = sentence
+            .tokens()
+            .iter()
+            .map(|t| t.kind())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Keyword,
+                TokenKind::Keyword,
+                TokenKind::Word,
+                TokenKind::Keyword,
+                TokenKind::Word,
+                TokenKind::Word,
+                TokenKind::QuestionMark
+            ]
+        );
+        match sentence.statement() {
+            Statement::HowMany { target, words } => {
+                assert_eq!(texts(target), vec!["Zorkmids"]);
+                assert_eq!(texts(words), vec!["glob", "Silver"])
+            }
+            other => panic!("unexpected statement {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_unit() {
+        match parse_sentence("glob \"Dark Matter\" is 34 Credits").statement() {
+            Statement::Unit {
+                words,
+                value,
+                currency,
+            } => {
+                assert_eq!(texts(words), vec!["glob", "Dark Matter"]);
+                assert_eq!(value.text(), "34");
+                assert_eq!(currency.text(), "Credits");
+            }
+            other => panic!("unexpected statement {:?}", other),
+        }
+        // needs at least one word before the unit
+        assert_eq!(
+            parse_sentence("Fish is 3 Credits").statement(),
+            &Statement::Unknown
+        );
+        assert_eq!(
+            parse_sentence("da Fish is three Credits").statement(),
+            &Statement::Unknown
+        );
+        assert_eq!(
+            parse_sentence("\"Dark Matter\" glob is 3 Credits").statement(),
+            &Statement::Unknown
+        );
+    }
+
+    #[test]
+    fn test_parse_numeral_needs_symbol() {
+        assert_eq!(parse_sentence("bla is ?").statement(), &Statement::Unknown);
+        assert_eq!(
+            parse_sentence("fi-ve is V").statement(),
+            &Statement::Unknown
+        );
+    }
+
+    #[test]
+    fn test_parse_unterminated_quote() {
+        let sentence = parse_sentence("glob \"Dust is 10 Credits");
+        assert_eq!(sentence.statement(), &Statement::Unknown);
+        assert!(sentence.tokens().is_empty());
+    }
+
+    #[test]
+    fn test_parse_exchange_rate() {
+        match parse_sentence("2 Zorkmids is 8 Credits").statement() {
+            Statement::ExchangeRate {
+                amount,
+                currency,
+                value,
+                target,
+            } => {
+                assert_eq!(
+                    vec![amount.text(), currency.text(), value.text(), target.text()],
+                    vec!["2", "Zorkmids", "8", "Credits"]
+                );
+            }
+            other => panic!("unexpected statement {:?}", other),
+        }
+        assert!(matches!(
+            parse_sentence("glob Silver is 3 Zorkmids").statement(),
+            Statement::Unit { .. }
+        ));
+        assert_eq!(
+            parse_sentence("1 Zorkmid is four Credits").statement(),
+            &Statement::Unknown
+        );
+    }
+
+    #[test]
+    fn test_parse_how_many_unit_target() {
+        match parse_sentence("how many Dark Matter is glob Gold ?").statement() {
+            Statement::HowMany { target, words } => {
+                assert_eq!(texts(target), vec!["Dark", "Matter"]);
+                assert_eq!(texts(words), vec!["glob", "Gold"]);
+            }
+            other => panic!("unexpected statement {:?}", other),
+        }
+        match parse_sentence("how many \"Dark Matter\" is glob Gold ?").statement() {
+            Statement::HowMany { target, .. } => {
+                assert_eq!(texts(target), vec!["Dark Matter"])
+            }
+            other => panic!("unexpected statement {:?}", other),
+        }
+        assert_eq!(
+            parse_sentence("how many is glob Gold ?").statement(),
+            &Statement::Unknown
+        );
+    }
+
+    #[test]
+    fn test_parse_how_to_say() {
+        for question in ["how do I say 42 ?", "What is 42 in alien?"] {
+            match parse_sentence(question).statement() {
+                Statement::HowToSay { number } => assert_eq!(number.text(), "42"),
+                other => panic!("unexpected statement {:?}", other),
+            }
+        }
+        assert_eq!(
+            parse_sentence("how do I say glob ?").statement(),
+            &Statement::Unknown
+        );
+        assert_eq!(
+            parse_sentence("what is 42 in alien").statement(),
+            &Statement::Unknown
+        );
+    }
+
+    #[test]
+    fn test_parse_purchase() {
+        for question in [
+            "how much Dark Matter can I buy for 100 Credits ?",
+            "How many Dark Matter is 100 Credits?",
+        ] {
+            match parse_sentence(question).statement() {
+                Statement::Purchase {
+                    target,
+                    value,
+                    currency,
+                } => {
+                    assert_eq!(texts(target), vec!["Dark", "Matter"]);
+                    assert_eq!(value.text(), "100");
+                    assert_eq!(currency.text(), "Credits");
+                }
+                other => panic!("unexpected statement {:?}", other),
+            }
+        }
+        assert!(matches!(
+            parse_sentence("how many Credits is glob Silver ?").statement(),
+            Statement::HowMany { .. }
+        ));
+        assert_eq!(
+            parse_sentence("how much Silver can I buy for glob Credits ?").statement(),
+            &Statement::Unknown
+        );
+    }
+
+    #[test]
+    fn test_parse_unit_decimal_value() {
+        for (sentence, value) in [
+            ("glob Silver is 17.5 Credits", "17.5"),
+            ("glob prok Gold is 57,800 Credits", "57,800"),
+        ] {
+            match parse_sentence(sentence).statement() {
+                Statement::Unit { value: v, .. } => assert_eq!(v.text(), value),
+                other => panic!("unexpected statement {:?}", other),
+            }
+        }
+        assert_eq!(
+            parse_sentence("glob Silver is 17,5 Credits").statement(),
+            &Statement::Unknown
+        );
+    }
+
+    #[test]
+    fn test_tokenize_comment() {
+        let tokens = tokenize("glob is I   # one, \"the\" first  ").unwrap();
+        assert_eq!(
+            texts(&tokens),
+            vec!["glob", "is", "I", "one, \"the\" first"]
+        );
+        assert_eq!(tokens[3].kind(), TokenKind::Comment);
+        assert_eq!(tokens[3].span().range(), 12..30);
+        // no comment inside quotes, a comment right after a word
+        let tokens = tokenize("glob \"Dust #1\" is 3 Credits#cheap").unwrap();
+        assert_eq!(
+            texts(&tokens),
+            vec!["glob", "Dust #1", "is", "3", "Credits", "cheap"]
+        );
+    }
+
+    #[test]
+    fn test_parse_comments() {
+        let input = "# prices from Vogon market, 2026\n\
+            glob is I # one\n\
+            \t# indented\n\
+            how much is glob ? # a question";
+        let sentences = parse(input);
+        assert_eq!(sentences.len(), 4);
+        assert_eq!(sentences[0].statement(), &Statement::Comment);
+        assert_eq!(sentences[0].text(), "");
+        assert_eq!(
+            sentences[0].comment().unwrap().text(),
+            "prices from Vogon market, 2026"
+        );
+        assert!(matches!(
+            sentences[1].statement(),
+            Statement::Numeral { .. }
+        ));
+        assert_eq!(sentences[1].text(), "glob is I");
+        assert_eq!(sentences[2].statement(), &Statement::Comment);
+        assert!(matches!(
+            sentences[3].statement(),
+            Statement::HowMuch { .. }
+        ));
+        for sentence in &sentences {
+            assert_eq!(&input[sentence.span().range()], sentence.text());
+            let comment = sentence.comment().unwrap();
+            assert!(input[comment.span().range()].starts_with('#'));
+            assert!(sentence
+                .tokens()
+                .iter()
+                .all(|t| t.kind() != TokenKind::Comment));
+        }
+    }
+
+    #[test]
+    fn test_parse_include() {
+        let sentence = parse_sentence("include \"shared/numerals.txt\" # all of them");
+        match sentence.statement() {
+            Statement::Include { path } => {
+                assert_eq!(path.text(), "shared/numerals.txt");
+                assert_eq!(path.span().range(), 8..29);
+            }
+            _ => panic!("not an include"),
+        }
+        assert_eq!(sentence.tokens()[0].kind(), TokenKind::Keyword);
+        // the path has to be quoted
+        assert_eq!(
+            parse_sentence("include numerals.txt").statement(),
+            &Statement::Unknown
+        );
+        // a numeral called include is still a numeral
+        assert!(matches!(
+            parse_sentence("include is I").statement(),
+            Statement::Numeral { .. }
+        ));
+    }
+}
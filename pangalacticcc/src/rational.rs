@@ -0,0 +1,537 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// Represents an exact fraction `numer / denom`.
+/// Values are always stored in lowest terms with a positive denominator,
+/// so two equal fractions have the same fields.
+/// Numerator and denominator are 128-bit integers. Calculations that do not fit
+/// are never rounded: the checked methods return None, the operators panic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    /// numerator, carries the sign, never i128::MIN
+    numer: i128,
+    /// denominator, always > 0
+    denom: i128,
+}
+
+/// Occurs when the result of a calculation does not fit into a Rational
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverflowError;
+impl Error for OverflowError {}
+impl Display for OverflowError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "number is too large to calculate with exactly")
+    }
+}
+
+/// Occurs when a string is not a decimal number or does not fit into a Rational
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseRationalError {
+    /// not a decimal number
+    Invalid,
+    /// a decimal number with too many digits
+    Overflow,
+}
+impl Error for ParseRationalError {}
+impl Display for ParseRationalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseRationalError::Invalid => write!(f, "invalid decimal number"),
+            ParseRationalError::Overflow => write!(f, "{}", OverflowError),
+        }
+    }
+}
+
+/// greatest common divisor, always non-negative
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
+impl Rational {
+    /// Creates a new fraction `numer / denom` in lowest terms.
+    /// # Panics
+    /// Panics if `denom` is zero or either is i128::MIN.
+    /// # Example
+    /// ```
+    /// use pangalacticcc::rational::Rational;
+    /// let r = Rational::new(10, 4);
+    /// assert_eq!(r.numer(), 5);
+    /// assert_eq!(r.denom(), 2);
+    /// ```
+    pub fn new(numer: i128, denom: i128) -> Self {
+        if denom == 0 {
+            panic!("denominator of a rational number must not be zero")
+        }
+        Rational::reduce(numer, denom).expect("rational number overflow")
+    }
+
+    /// Returns `numer / denom` in lowest terms, or None if it does not fit.
+    /// `denom` must not be zero.
+    fn reduce(numer: i128, denom: i128) -> Option<Self> {
+        // i128::MIN has no positive counterpart
+        if numer == i128::MIN || denom == i128::MIN {
+            return None;
+        }
+        let divisor = gcd(numer, denom);
+        let sign = if denom < 0 { -1 } else { 1 };
+        Some(Rational {
+            numer: sign * numer / divisor,
+            denom: sign * denom / divisor,
+        })
+    }
+
+    /// Returns the numerator (in lowest terms)
+    pub fn numer(&self) -> i128 {
+        self.numer
+    }
+
+    /// Returns the denominator (in lowest terms, always positive)
+    pub fn denom(&self) -> i128 {
+        self.denom
+    }
+
+    /// Returns `self + rhs`, or None if the result does not fit
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        // add over the least common denominator to keep the numbers small
+        let divisor = gcd(self.denom, rhs.denom);
+        let (left, right) = (rhs.denom / divisor, self.denom / divisor);
+        let numer = self
+            .numer
+            .checked_mul(left)?
+            .checked_add(rhs.numer.checked_mul(right)?)?;
+        Rational::reduce(numer, self.denom.checked_mul(left)?)
+    }
+
+    /// Returns `self - rhs`, or None if the result does not fit
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(-rhs)
+    }
+
+    /// Returns `self * rhs`, or None if the result does not fit
+    /// # Example
+    /// ```
+    /// use pangalacticcc::rational::Rational;
+    /// let big = Rational::from(i64::MAX);
+    /// assert_eq!(big.checked_mul(big).unwrap().numer(), i64::MAX as i128 * i64::MAX as i128);
+    /// assert_eq!(big.checked_mul(big).unwrap().checked_mul(big), None);
+    /// ```
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        // cancel common factors first, so the result only overflows if it has to
+        let g1 = gcd(self.numer, rhs.denom);
+        let g2 = gcd(rhs.numer, self.denom);
+        let numer = (self.numer / g1).checked_mul(rhs.numer / g2)?;
+        let denom = (self.denom / g2).checked_mul(rhs.denom / g1)?;
+        Rational::reduce(numer, denom)
+    }
+
+    /// Returns `self / rhs`, or None if `rhs` is zero or the result does not fit
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        if rhs.numer == 0 {
+            return None;
+        }
+        self.checked_mul(Rational::reduce(rhs.denom, rhs.numer)?)
+    }
+
+    /// Returns true if the value is a whole number
+    pub fn is_integer(&self) -> bool {
+        self.denom == 1
+    }
+
+    /// Returns true if the value can be written as a decimal number with finitely many digits,
+    /// i.e. the denominator has no prime factors other than 2 and 5.
+    pub fn is_terminating(&self) -> bool {
+        let mut d = self.denom;
+        for p in [2, 5] {
+            while d % p == 0 {
+                d /= p;
+            }
+        }
+        d == 1
+    }
+
+    /// Returns the value as decimal string, correctly rounded (half away from zero)
+    /// to `places` digits after the decimal point.
+    /// # Example
+    /// ```
+    /// use pangalacticcc::rational::Rational;
+    /// assert_eq!(Rational::new(10, 3).to_decimal_string(2), "3.33");
+    /// assert_eq!(Rational::new(-2, 3).to_decimal_string(3), "-0.667");
+    /// assert_eq!(Rational::new(391, 2).to_decimal_string(0), "196");
+    /// ```
+    pub fn to_decimal_string(&self, places: usize) -> String {
+        let denom = self.denom as u128;
+        let mut int_part = self.numer.unsigned_abs() / denom;
+        let mut rest = self.numer.unsigned_abs() % denom;
+        // long division, so nothing is scaled beyond 128 bits
+        let mut digits = Vec::with_capacity(places);
+        for _ in 0..places {
+            let (digit, next) = times_ten(rest, denom);
+            digits.push(digit);
+            rest = next;
+        }
+        // round half away from zero
+        if 2 * rest >= denom {
+            let carry = digits.iter_mut().rev().all(|d| {
+                *d = (*d + 1) % 10;
+                *d == 0
+            });
+            if carry {
+                int_part += 1;
+            }
+        }
+        let is_zero = int_part == 0 && digits.iter().all(|d| *d == 0);
+        let sign = if self.numer < 0 && !is_zero { "-" } else { "" };
+        let digits = digits
+            .iter()
+            .map(|d| char::from(b'0' + d))
+            .collect::<String>();
+        if places == 0 {
+            format!("{}{}", sign, int_part)
+        } else {
+            format!("{}{}.{}", sign, int_part, digits)
+        }
+    }
+
+    /// Returns the number of decimal places needed to write a terminating fraction exactly
+    fn decimal_places(&self) -> usize {
+        let mut d = self.denom;
+        let mut places = 0;
+        while d != 1 {
+            // a terminating denominator 2^a * 5^b needs max(a, b) places
+            if d % 10 == 0 {
+                d /= 10
+            } else if d % 2 == 0 {
+                d /= 2
+            } else {
+                d /= 5
+            }
+            places += 1;
+        }
+        places
+    }
+}
+
+/// Returns (10 * rest / denom, 10 * rest % denom) without overflowing, `rest` must be below `denom`
+fn times_ten(rest: u128, denom: u128) -> (u8, u128) {
+    let (mut digit, mut acc) = (0, 0);
+    for _ in 0..10 {
+        // acc and rest are below denom < 2^127, so the sum fits
+        acc += rest;
+        if acc >= denom {
+            acc -= denom;
+            digit += 1;
+        }
+    }
+    (digit, acc)
+}
+
+impl Display for Rational {
+    /// Whole numbers are shown without a fractional part, fractions with a finite decimal
+    /// expansion are shown as exact decimals, anything else as `numer/denom`.
+    /// If a precision is given (e.g. `{:.2}`), the value is shown as correctly rounded decimal.
+    /// # Example
+    /// ```
+    /// use pangalacticcc::rational::Rational;
+    /// assert_eq!(Rational::from(782).to_string(), "782");
+    /// assert_eq!(Rational::new(1, 2).to_string(), "0.5");
+    /// assert_eq!(Rational::new(10, 3).to_string(), "10/3");
+    /// assert_eq!(format!("{:.3}", Rational::new(10, 3)), "3.333");
+    /// ```
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(places) = f.precision() {
+            write!(f, "{}", self.to_decimal_string(places))
+        } else if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else if self.is_terminating() {
+            write!(f, "{}", self.to_decimal_string(self.decimal_places()))
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Rational {
+            numer: value as i128,
+            denom: 1,
+        }
+    }
+}
+
+impl From<i32> for Rational {
+    fn from(value: i32) -> Self {
+        Rational::from(value as i64)
+    }
+}
+
+impl From<i128> for Rational {
+    /// # Panics
+    /// Panics if `value` is i128::MIN.
+    fn from(value: i128) -> Self {
+        Rational::new(value, 1)
+    }
+}
+
+impl FromStr for Rational {
+    type Err = ParseRationalError;
+
+    /// Parses a decimal number exactly, e.g. "17.5" as 35/2.
+    /// The whole part may be grouped by commas in thousands, e.g. "57,800" or "1,234.5".
+    /// # Example
+    /// ```
+    /// use pangalacticcc::rational::Rational;
+    /// assert_eq!("17.5".parse::<Rational>(), Ok(Rational::new(35, 2)));
+    /// assert_eq!("1,234.25".parse::<Rational>(), Ok(Rational::new(4937, 4)));
+    /// assert!("1,23".parse::<Rational>().is_err());
+    /// assert!("17.".parse::<Rational>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (sign, digits) = match s.strip_prefix('-') {
+            Some(rest) => (-1, rest),
+            None => (1, s),
+        };
+        let (whole, fraction) = match digits.split_once('.') {
+            Some((whole, fraction)) => (whole, fraction),
+            None => (digits, ""),
+        };
+        let groups = whole.split(',').collect::<Vec<_>>();
+        let grouped =
+            groups.len() == 1 || (groups[0].len() <= 3 && groups[1..].iter().all(|g| g.len() == 3));
+        let all_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+        if !grouped
+            || !groups.iter().all(|g| all_digits(g))
+            || (digits.contains('.') && !all_digits(fraction))
+        {
+            return Err(ParseRationalError::Invalid);
+        }
+        let numer = format!("{}{}", groups.concat(), fraction)
+            .parse::<i128>()
+            .map_err(|_| ParseRationalError::Overflow)?;
+        let denom = 10i128
+            .checked_pow(fraction.len() as u32)
+            .ok_or(ParseRationalError::Overflow)?;
+        Ok(Rational::new(sign * numer, denom))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // compare whole parts, then the reciprocals of the remainders (a continued fraction),
+        // cross multiplying could overflow
+        let (mut a, mut b, mut c, mut d) = (self.numer, self.denom, other.numer, other.denom);
+        let mut reversed = false;
+        loop {
+            let order = a.div_euclid(b).cmp(&c.div_euclid(d));
+            let (r1, r2) = (a.rem_euclid(b), c.rem_euclid(d));
+            let order = match order {
+                Ordering::Equal if r1 == 0 || r2 == 0 => r1.cmp(&r2),
+                Ordering::Equal => {
+                    // r1/b < r2/d if b/r1 > d/r2
+                    (a, b, c, d) = (b, r1, d, r2);
+                    reversed = !reversed;
+                    continue;
+                }
+                order => order,
+            };
+            return if reversed { order.reverse() } else { order };
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+    /// # Panics
+    /// Panics if the result does not fit, see checked_add.
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs).expect("rational number overflow")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+    /// # Panics
+    /// Panics if the result does not fit, see checked_sub.
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs).expect("rational number overflow")
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+    /// # Panics
+    /// Panics if the result does not fit, see checked_mul.
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs).expect("rational number overflow")
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+    /// # Panics
+    /// Panics if `rhs` is zero or the result does not fit, see checked_div.
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.numer == 0 {
+            panic!("division of a rational number by zero")
+        }
+        self.checked_div(rhs).expect("rational number overflow")
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+    fn neg(self) -> Self::Output {
+        Rational {
+            numer: -self.numer,
+            denom: self.denom,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rational_lowest_terms() {
+        let r = Rational::new(57800, 4);
+        assert_eq!(r, Rational::from(14450));
+        let r = Rational::new(3, -6);
+        assert_eq!((r.numer(), r.denom()), (-1, 2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rational_zero_denominator() {
+        let _that_wont_work = Rational::new(1, 0);
+    }
+
+    #[test]
+    fn test_rational_dirt_round_trip_exact() {
+        let price = Rational::new(10, 3);
+        assert_eq!(price * Rational::from(3), Rational::from(10));
+    }
+
+    #[test]
+    fn test_rational_arithmetic() {
+        let half = Rational::new(1, 2);
+        let third = Rational::new(1, 3);
+        assert_eq!(half + third, Rational::new(5, 6));
+        assert_eq!(half - third, Rational::new(1, 6));
+        assert_eq!(half * third, Rational::new(1, 6));
+        assert_eq!(half / third, Rational::new(3, 2));
+    }
+
+    #[test]
+    fn test_rational_ordering() {
+        assert!(Rational::new(1, 3) < Rational::new(1, 2));
+        assert!(Rational::new(-1, 2) < Rational::from(0));
+    }
+
+    #[test]
+    fn test_rational_display() {
+        assert_eq!(Rational::from(57800).to_string(), "57800");
+        assert_eq!(Rational::new(391, 2).to_string(), "195.5");
+        assert_eq!(Rational::new(1, 8).to_string(), "0.125");
+        assert_eq!(Rational::new(-7, 3).to_string(), "-7/3");
+    }
+
+    #[test]
+    fn test_rational_display_rounded() {
+        assert_eq!(format!("{:.2}", Rational::new(2, 3)), "0.67");
+        assert_eq!(format!("{:.1}", Rational::new(1, 4)), "0.3");
+        assert_eq!(format!("{:.0}", Rational::new(-1, 3)), "0");
+        assert_eq!(format!("{:.2}", Rational::from(10)), "10.00");
+    }
+
+    #[test]
+    fn test_rational_from_str() {
+        assert_eq!("782".parse::<Rational>(), Ok(Rational::from(782)));
+        assert_eq!("0.125".parse::<Rational>(), Ok(Rational::new(1, 8)));
+        assert_eq!("-2.5".parse::<Rational>(), Ok(Rational::new(-5, 2)));
+        assert_eq!("57,800".parse::<Rational>(), Ok(Rational::from(57800)));
+        assert_eq!(
+            "1,000,000.5".parse::<Rational>(),
+            Ok(Rational::new(2000001, 2))
+        );
+        for invalid in ["", ".5", "1.2.3", "1,0000", ",100", "12a"] {
+            assert_eq!(
+                invalid.parse::<Rational>(),
+                Err(ParseRationalError::Invalid)
+            );
+        }
+        assert_eq!(
+            "99999999999999999999".parse::<Rational>(),
+            Ok(Rational::new(99999999999999999999, 1))
+        );
+        assert_eq!(
+            "1000000000000000000000000000000000000000".parse::<Rational>(),
+            Err(ParseRationalError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_rational_beyond_64_bits() {
+        let big = Rational::from(i64::MAX);
+        let square = big * big;
+        assert_eq!(square.numer(), i64::MAX as i128 * i64::MAX as i128);
+        assert_eq!(square / big, big);
+        assert!(square > big);
+        assert_eq!(
+            (square + Rational::new(1, 2)).to_string(),
+            "85070591730234615847396907784232501249.5"
+        );
+    }
+
+    #[test]
+    fn test_rational_checked_overflow() {
+        let max = Rational::new(i128::MAX, 1);
+        assert_eq!(max.checked_add(Rational::from(1)), None);
+        assert_eq!(max.checked_mul(Rational::from(2)), None);
+        assert_eq!((-max).checked_sub(Rational::from(1)), None);
+        assert_eq!(Rational::from(1).checked_div(Rational::from(0)), None);
+        // common factors cancel before multiplying
+        assert_eq!(
+            max.checked_mul(Rational::new(1, i128::MAX)),
+            Some(Rational::from(1))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn test_rational_operator_overflow_panics() {
+        let _too_large = Rational::new(i128::MAX, 1) + Rational::from(1);
+    }
+
+    #[test]
+    fn test_rational_ordering_without_overflow() {
+        let a = Rational::new(i128::MAX - 1, i128::MAX);
+        let b = Rational::new(i128::MAX - 2, i128::MAX - 1);
+        assert!(b < a);
+        assert!(Rational::new(-i128::MAX, 3) < Rational::new(-i128::MAX, 7));
+        assert_eq!(a.cmp(&a), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_rational_display_rounded_large() {
+        let a = Rational::new(2, 3) + Rational::from(i64::MAX);
+        assert_eq!(format!("{:.2}", a), "9223372036854775807.67");
+        assert_eq!(format!("{:.1}", Rational::new(-19, 20)), "-1.0");
+        assert_eq!(format!("{:.1}", Rational::new(99, 100)), "1.0");
+    }
+}
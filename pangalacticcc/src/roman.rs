@@ -1,8 +1,16 @@
 use lazy_static::lazy_static;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::iter::Sum;
+use std::ops::{Add, Bound, Div, Mul, RangeBounds, Sub};
 use std::str::FromStr;
 
+use crate::rational::Rational;
+
 lazy_static! {
     pub static ref ROMAN_VALUES: HashMap<char, i32> = {
         let mut map = HashMap::new();
@@ -13,23 +21,159 @@ lazy_static! {
         map.insert('X', 10);
         map.insert('V', 5);
         map.insert('I', 1);
+        map.insert('N', 0);
         map
     };
 }
 
+/// The rule a numeral violates, see FromStr for Roman
+#[derive(Debug, Clone, PartialEq)]
+pub enum RomanRule {
+    /// the input is empty
+    Empty,
+    /// the input contains a character that is not part of any symbol
+    InvalidSymbol(char),
+    /// I, X, C, M and their overlined forms may be repeated three times at most
+    TooManyRepeats(String),
+    /// V, L, D and their overlined forms may never be repeated
+    NeverRepeated(String),
+    /// V, L, D and their overlined forms may never be subtracted
+    NeverSubtracted(String),
+    /// a symbol may only be subtracted from the next two larger symbols, e.g. I from V and X
+    InvalidSubtraction(String, String),
+    /// only one smaller symbol may be subtracted from a larger one
+    MultipleSubtraction(String, String),
+    /// the value is not within 0..=MAX_VALUE
+    OutOfRange,
+    /// all rules above are met, but the symbols are not in canonical order
+    NonCanonical(String),
+    /// a whole number symbol follows a fraction, fractions come last
+    AfterFraction(char),
+    /// S (one half) is written after twelfths
+    FractionOrder,
+    /// six or more twelfths, six twelfths are written as S
+    TooManyTwelfths,
+    /// N (nulla, zero) is combined with other symbols
+    NullaNotAlone,
+}
+
+impl Display for RomanRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RomanRule::Empty => write!(f, "empty numeral"),
+            RomanRule::InvalidSymbol(c) => write!(f, "{} is not a roman symbol", c),
+            RomanRule::TooManyRepeats(s) => write!(f, "more than three repeats of {}", s),
+            RomanRule::NeverRepeated(s) => write!(f, "{} may never be repeated", s),
+            RomanRule::NeverSubtracted(s) => {
+                write!(f, "{} may never precede a larger symbol", s)
+            }
+            RomanRule::InvalidSubtraction(small, large) => {
+                write!(f, "{} may not be subtracted from {}", small, large)
+            }
+            RomanRule::MultipleSubtraction(small, large) => {
+                write!(f, "only one {} may be subtracted from {}", small, large)
+            }
+            RomanRule::OutOfRange => write!(f, "value out of range"),
+            RomanRule::NonCanonical(expected) => {
+                write!(
+                    f,
+                    "symbols are not in canonical order, expected {}",
+                    expected
+                )
+            }
+            RomanRule::AfterFraction(c) => write!(f, "{} may not follow a fraction", c),
+            RomanRule::FractionOrder => write!(f, "S must precede the twelfths"),
+            RomanRule::TooManyTwelfths => {
+                write!(f, "more than five twelfths, six twelfths are written as S")
+            }
+            RomanRule::NullaNotAlone => write!(f, "N (zero) may only stand alone"),
+        }
+    }
+}
+
 /// Occurs when the input could not be converted to Roman,
 /// e.g. due to characters being invalid or in unsupported order.
+/// Holds the rule that was violated and, if it can be attributed to a single symbol,
+/// the index of the character where that symbol starts.
+/// # Example
+/// ```
+/// use pangalacticcc::roman::{Roman, RomanRule};
+/// let e = "VL".parse::<Roman>().unwrap_err();
+/// assert_eq!(e.rule(), &RomanRule::NeverSubtracted("V".to_string()));
+/// assert_eq!(e.position(), Some(0));
+/// assert_eq!(e.to_string(), "invalid roman numeral: V may never precede a larger symbol (at 0)");
+/// ```
 #[derive(Debug, Clone, PartialEq)]
-pub struct ParseRomanNumeralError;
+pub struct ParseRomanNumeralError {
+    /// the rule that was broken
+    rule: RomanRule,
+    /// character index of the offending symbol, if any
+    position: Option<usize>,
+}
+
+impl ParseRomanNumeralError {
+    /// Creates a new error for `rule` broken at character index `position`
+    pub fn new(rule: RomanRule, position: Option<usize>) -> Self {
+        ParseRomanNumeralError { rule, position }
+    }
+    /// Returns the rule that was broken
+    pub fn rule(&self) -> &RomanRule {
+        &self.rule
+    }
+    /// Returns the character index of the symbol that broke the rule, if any
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+}
+
+impl Error for ParseRomanNumeralError {}
 
 impl Display for ParseRomanNumeralError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "invalid string for roman numeral")
+        match self.position {
+            Some(position) => write!(f, "invalid roman numeral: {} (at {})", self.rule, position),
+            None => write!(f, "invalid roman numeral: {}", self.rule),
+        }
+    }
+}
+
+/// Occurs when a number cannot be converted to Roman,
+/// i.e. it is not within 0..=MAX_VALUE.
+/// # Example
+/// ```
+/// use std::convert::TryFrom;
+/// use pangalacticcc::roman::Roman;
+/// let roman_i = Roman::try_from(1).unwrap();
+/// assert_eq!(roman_i.get_value(), 1);
+/// assert_eq!(roman_i.get_representation(),"I");
+/// let roman_big = Roman::try_from(5001u64).unwrap();
+/// assert_eq!(roman_big.get_representation(),"V\u{305}I");
+/// assert_eq!(Roman::try_from(0).unwrap().get_representation(), "N");
+/// assert!(Roman::try_from(4_000_000u32).is_err());
+/// assert!(Roman::try_from(-1i8).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RomanRangeError {
+    /// the number that could not be converted, as text to support every integer width
+    value: String,
+}
+
+impl Error for RomanRangeError {}
+
+impl Display for RomanRangeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} cannot be converted. Hint: Only values from 0 to {} can be represented.",
+            self.value, MAX_VALUE
+        )
     }
 }
 
-/// Represents a Roman number
-#[derive(Debug)]
+/// Represents a Roman number.
+/// Two Roman numbers are equal if their values are equal,
+/// regardless of the notation they were written in.
+#[derive(Debug, Clone)]
 pub struct Roman {
     /// value represented in Roman numerals
     repr: String,
@@ -43,89 +187,491 @@ impl Display for Roman {
     }
 }
 
-// from integer to Roman numeral
-impl From<u32> for Roman {
-    /// Converts from unsigned integer to Roman
-    /// if num is within bounds 0<=num<4000
-    /// # Arguments
-    /// * `num` - unsigned int smaller than 4000
+/// Combining overline (vinculum), multiplies the preceding symbol by 1000
+pub const VINCULUM: char = '\u{305}';
+/// Reversed C used in apostrophus notation, `)` is accepted as ASCII replacement
+pub const APOSTROPHUS: char = '\u{2183}';
+/// Nulla, zero, may only be used on its own
+pub const NULLA: char = 'N';
+/// Largest value that can be represented, M̅M̅M̅C̅M̅X̅C̅MX̅CMXCIX
+pub const MAX_VALUE: i32 = 3_999_999;
+/// Semis, one half, written after the whole number
+pub const SEMIS: char = 'S';
+/// Uncia (middle dot), one twelfth, written after the whole number and S.
+/// `.` and `•` are accepted as replacement
+pub const UNCIA: char = '\u{B7}';
+
+lazy_static! {
+    /// Values of the symbols written with a vinculum (overline).
+    /// I̅ is treated as alias of M.
+    pub static ref EXTENDED_VALUES: HashMap<&'static str, i32> = {
+        let mut map = HashMap::new();
+        map.insert("M\u{305}", 1_000_000);
+        map.insert("D\u{305}", 500_000);
+        map.insert("C\u{305}", 100_000);
+        map.insert("L\u{305}", 50_000);
+        map.insert("X\u{305}", 10_000);
+        map.insert("V\u{305}", 5000);
+        map
+    };
+}
+
+/// Apostrophus groups and the equivalent symbols in vinculum notation,
+/// ordered so that longer groups are matched first.
+const APOSTROPHUS_GROUPS: [(&str, &str); 8] = [
+    ("CCCCI\u{2183}\u{2183}\u{2183}\u{2183}", "M\u{305}"),
+    ("CCCI\u{2183}\u{2183}\u{2183}", "C\u{305}"),
+    ("I\u{2183}\u{2183}\u{2183}\u{2183}", "D\u{305}"),
+    ("CCI\u{2183}\u{2183}", "X\u{305}"),
+    ("I\u{2183}\u{2183}\u{2183}", "L\u{305}"),
+    ("CI\u{2183}", "M"),
+    ("I\u{2183}\u{2183}", "V\u{305}"),
+    ("I\u{2183}", "D"),
+];
+
+/// Notation used for values of 1000 and above
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Notation {
+    /// overlined symbols multiply by 1000, e.g. V̅ for 5000
+    Vinculum,
+    /// groups of C, I and reversed C (Ↄ), e.g. IↃↃ for 5000 or CIↃ for 1000
+    Apostrophus,
+}
+
+/// Decides how strictly numerals are checked when parsing
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ParseMode {
+    /// only canonical numerals are accepted, e.g. IV but not IIII
+    #[default]
+    Strict,
+    /// additive and other non-canonical forms are accepted, e.g. IIII, IIX or XIIX
+    Lenient,
+}
+
+/// Glyphs used to write Roman numerals
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Style {
+    /// ASCII capitals, e.g. XII
+    #[default]
+    Ascii,
+    /// ASCII lowercase letters, e.g. xii
+    Lowercase,
+    /// Roman numeral characters from the Unicode Number Forms block, e.g. ⅩⅬⅠⅠ,
+    /// numbers up to 12 are written as one character, e.g. Ⅻ
+    Unicode,
+    /// small Roman numeral characters from the Unicode Number Forms block, e.g. ⅹⅼⅰⅰ or ⅻ
+    UnicodeLowercase,
+}
+
+impl Style {
+    /// Returns the style `s` is written in.
+    /// Capitals and ASCII are assumed if `s` does not contain any lowercase or Unicode glyphs.
     /// # Example
     /// ```
-    /// use pangalacticcc::roman::Roman;
-    /// let roman_i = Roman::from(1);
-    /// assert_eq!(roman_i.get_value(), 1);
-    /// assert_eq!(roman_i.get_representation(),"I")
+    /// use pangalacticcc::roman::Style;
+    /// assert_eq!(Style::detect("XII"), Style::Ascii);
+    /// assert_eq!(Style::detect("xii"), Style::Lowercase);
+    /// assert_eq!(Style::detect("\u{216B}"), Style::Unicode);
+    /// assert_eq!(Style::detect("\u{2179}\u{2170}"), Style::UnicodeLowercase);
     /// ```
-    fn from(num: u32) -> Self {
-        if num >= 4000 {
-            panic!(
-                "{} cannot be converted. Hint: Only uint values <= 4000 can be represented. ",
-                num
-            )
+    pub fn detect(s: &str) -> Style {
+        // reversed C is used in apostrophus notation regardless of the style
+        let unicode = s
+            .chars()
+            .any(|c| ('\u{2160}'..='\u{2188}').contains(&c) && c != APOSTROPHUS && c != '\u{2184}');
+        let lowercase = s
+            .chars()
+            .any(|c| "ivxlcdmns\u{2184}".contains(c) || ('\u{2170}'..='\u{217F}').contains(&c));
+        match (unicode, lowercase) {
+            (true, true) => Style::UnicodeLowercase,
+            (true, false) => Style::Unicode,
+            (false, true) => Style::Lowercase,
+            (false, false) => Style::Ascii,
         }
-        let mut result = "".to_string();
-        let thousands = num / 1000;
-        let hundreds = (num - thousands * 1000) / 100;
-        let tens = (num - thousands * 1000 - hundreds * 100) / 10;
-        let units = num - thousands * 1000 - hundreds * 100 - tens * 10;
-        //todo: refactor
+    }
+}
 
-        if let 0..=3 = thousands {
-            for _ in 0..thousands {
-                result.push('M')
-            }
+/// Unicode glyphs for 1 to 12, e.g. Ⅻ for XII
+const UNICODE_NUMBERS: [&str; 12] = [
+    "I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X", "XI", "XII",
+];
+
+/// Returns the symbols in vinculum or apostrophus notation `c` stands for,
+/// if it is a lowercase or Unicode Roman numeral glyph or `)`
+fn expand_glyph(c: char) -> Option<&'static str> {
+    let expanded = match c {
+        'i' => "I",
+        'v' => "V",
+        'x' => "X",
+        'l' => "L",
+        'c' => "C",
+        'd' => "D",
+        'm' => "M",
+        'n' => "N",
+        ')' | '\u{2184}' => "\u{2183}",
+        // Ⅰ to Ⅻ and ⅰ to ⅻ
+        '\u{2160}'..='\u{216B}' => UNICODE_NUMBERS[c as usize - 0x2160],
+        '\u{2170}'..='\u{217B}' => UNICODE_NUMBERS[c as usize - 0x2170],
+        '\u{216C}' | '\u{217C}' => "L",
+        '\u{216D}' | '\u{217D}' => "C",
+        '\u{216E}' | '\u{217E}' => "D",
+        '\u{216F}' | '\u{217F}' | '\u{2180}' => "M",
+        '\u{2181}' => "V\u{305}",
+        '\u{2182}' => "X\u{305}",
+        '\u{2185}' => "VI",
+        '\u{2186}' => "L",
+        '\u{2187}' => "L\u{305}",
+        '\u{2188}' => "C\u{305}",
+        _ => return None,
+    };
+    Some(expanded)
+}
+
+/// Returns SEMIS or UNCIA if `c` is a fraction symbol
+fn fraction_symbol(c: char) -> Option<char> {
+    match c {
+        'S' | 's' => Some(SEMIS),
+        '\u{B7}' | '.' | '\u{2022}' => Some(UNCIA),
+        _ => None,
+    }
+}
+
+/// Returns true if `s` is written in apostrophus notation
+fn uses_apostrophus(s: &str) -> bool {
+    s.contains([APOSTROPHUS, ')', '\u{2184}'])
+}
+
+/// Returns `numeral`, written in ASCII capitals, in the given style.
+/// `numeral` does not need to be canonical, e.g. for showing how non-canonical input was read.
+/// # Example
+/// ```
+/// use pangalacticcc::roman::{apply_style, Style};
+/// assert_eq!(apply_style("IIX", Style::Lowercase), "iix");
+/// assert_eq!(apply_style("XII", Style::Unicode), "\u{216B}");
+/// ```
+pub fn apply_style(numeral: &str, style: Style) -> String {
+    let unicode = |c: char, offset: u32| match c {
+        'L' => char::from_u32(0x216C + offset),
+        'C' => char::from_u32(0x216D + offset),
+        'D' => char::from_u32(0x216E + offset),
+        'M' => char::from_u32(0x216F + offset),
+        'I' => char::from_u32(0x2160 + offset),
+        'V' => char::from_u32(0x2164 + offset),
+        'X' => char::from_u32(0x2169 + offset),
+        APOSTROPHUS if offset != 0 => Some('\u{2184}'),
+        _ => None,
+    };
+    let offset = match style {
+        Style::Ascii => return numeral.to_string(),
+        Style::Lowercase => {
+            return numeral
+                .chars()
+                .map(|c| {
+                    if c == APOSTROPHUS {
+                        '\u{2184}'
+                    } else {
+                        c.to_ascii_lowercase()
+                    }
+                })
+                .collect()
         }
+        Style::Unicode => 0,
+        Style::UnicodeLowercase => 0x10,
+    };
+    if let Some(i) = UNICODE_NUMBERS.iter().position(|n| *n == numeral) {
+        return char::from_u32(0x2160 + offset + i as u32)
+            .map(|c| c.to_string())
+            .unwrap_or_default();
+    }
+    numeral
+        .chars()
+        .map(|c| unicode(c, offset).unwrap_or(c))
+        .collect()
+}
 
-        match hundreds {
-            0..=3 => {
-                for _ in 0..hundreds {
-                    result.push('C')
+/// Splits `s` into symbols in vinculum notation, each with the index of the character it starts at.
+/// Apostrophus groups are replaced by their vinculum equivalent, I̅ is replaced by M.
+/// Lowercase and Unicode glyphs are replaced by the symbols they stand for,
+/// e.g. Ⅻ is split into X, I, I, all starting at the index of Ⅻ.
+/// Returns ParseRomanNumeralError if `s` contains anything that is not a symbol.
+fn tokenize(s: &str) -> Result<Vec<(usize, &'static str)>, ParseRomanNumeralError> {
+    // expanded characters with the index of the character in `s` they come from
+    let mut chars = Vec::new();
+    let mut origins = Vec::new();
+    for (i, c) in s.chars().enumerate() {
+        match expand_glyph(c) {
+            Some(expanded) => {
+                for e in expanded.chars() {
+                    chars.push(e);
+                    origins.push(i);
                 }
             }
-            4 => result.push_str("CD"),
-            5 => result.push('D'),
-            6 => result.push_str("DC"),
-            7 => result.push_str("DCC"),
-            8 => result.push_str("DCCC"),
-            9 => result.push_str("CM"),
-            _ => {}
-        }
-        match tens {
-            0..=3 => {
-                for _ in 0..tens {
-                    result.push('X')
+            None => {
+                chars.push(c);
+                origins.push(i);
+            }
+        }
+    }
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    'outer: while i < chars.len() {
+        for (group, symbol) in APOSTROPHUS_GROUPS {
+            let n = group.chars().count();
+            if chars[i..].iter().copied().take(n).eq(group.chars()) {
+                tokens.push((origins[i], symbol));
+                i += n;
+                continue 'outer;
+            }
+        }
+        let c = chars[i];
+        let invalid = ParseRomanNumeralError::new(RomanRule::InvalidSymbol(c), Some(origins[i]));
+        if chars.get(i + 1) == Some(&VINCULUM) {
+            if c == 'I' {
+                tokens.push((origins[i], "M"));
+            } else {
+                match EXTENDED_VALUES.keys().find(|k| k.starts_with(c)) {
+                    Some(symbol) => tokens.push((origins[i], symbol)),
+                    None => return Err(invalid),
                 }
             }
-            4 => result.push_str("XL"),
-            5 => result.push('L'),
-            6 => result.push_str("LX"),
-            7 => result.push_str("LXX"),
-            8 => result.push_str("LXXX"),
-            9 => result.push_str("XC"),
-            _ => {}
-        }
-        match units {
-            0..=3 => {
-                for _ in 0..units {
-                    result.push('I')
+            i += 2;
+        } else {
+            tokens.push((
+                origins[i],
+                match c {
+                    'M' => "M",
+                    'D' => "D",
+                    'C' => "C",
+                    'L' => "L",
+                    'X' => "X",
+                    'V' => "V",
+                    'I' => "I",
+                    'N' => "N",
+                    _ => return Err(invalid),
+                },
+            ));
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+/// Returns true if the numeral is just N (zero), fails if N is combined with other symbols
+fn check_nulla(tokens: &[(usize, &'static str)]) -> Result<bool, ParseRomanNumeralError> {
+    match tokens.iter().find(|(_, t)| *t == "N") {
+        None => Ok(false),
+        Some(_) if tokens.len() == 1 => Ok(true),
+        Some((i, _)) => Err(ParseRomanNumeralError::new(
+            RomanRule::NullaNotAlone,
+            Some(*i),
+        )),
+    }
+}
+
+/// Returns true for V, L, D and their overlined forms
+fn is_five(symbol: &str) -> bool {
+    matches!(symbol.chars().next(), Some('V') | Some('L') | Some('D'))
+}
+
+/// Checks the rules listed in FromStr for Roman and returns the first violation
+fn check_rules(tokens: &[(usize, &'static str)]) -> Result<(), ParseRomanNumeralError> {
+    let mut run = 0;
+    for (i, (position, symbol)) in tokens.iter().enumerate() {
+        let err = |rule| Err(ParseRomanNumeralError::new(rule, Some(*position)));
+        let value = symbol_value(symbol);
+        // repeats
+        if i > 0 && tokens[i - 1].1 == *symbol {
+            run += 1;
+        } else {
+            run = 1;
+        }
+        if run == 2 && is_five(symbol) {
+            return err(RomanRule::NeverRepeated(symbol.to_string()));
+        }
+        if run == 4 {
+            return err(RomanRule::TooManyRepeats(symbol.to_string()));
+        }
+        // subtraction
+        if let Some((_, next)) = tokens.get(i + 1) {
+            let next_value = symbol_value(next);
+            if value < next_value {
+                if is_five(symbol) {
+                    return err(RomanRule::NeverSubtracted(symbol.to_string()));
+                }
+                if next_value > 10 * value {
+                    return err(RomanRule::InvalidSubtraction(
+                        symbol.to_string(),
+                        next.to_string(),
+                    ));
+                }
+                if run > 1 {
+                    let (first, _) = tokens[i + 1 - run];
+                    return Err(ParseRomanNumeralError::new(
+                        RomanRule::MultipleSubtraction(symbol.to_string(), next.to_string()),
+                        Some(first),
+                    ));
                 }
             }
-            4 => result.push_str("IV"),
-            5 => result.push('V'),
-            6 => result.push_str("VI"),
-            7 => result.push_str("VII"),
-            8 => result.push_str("VIII"),
-            9 => result.push_str("IX"),
-            _ => {}
         }
+    }
+    Ok(())
+}
+
+/// Returns the value of a single symbol in vinculum notation
+fn symbol_value(symbol: &str) -> i32 {
+    match EXTENDED_VALUES.get(symbol) {
+        Some(v) => *v,
+        None => ROMAN_VALUES[&symbol.chars().next().unwrap()],
+    }
+}
 
-        Roman {
-            repr: result,
-            value: num as i32,
+/// Returns the symbol in vinculum notation if `s` consists of exactly one symbol
+/// in any supported notation, e.g. "X", "V̅", "IↃↃ" or "CCI))",
+/// or the fraction symbol if `s` is S or a twelfth.
+/// # Example
+/// ```
+/// use pangalacticcc::roman::normalize_symbol;
+/// assert_eq!(normalize_symbol("X"), Some("X".to_string()));
+/// assert_eq!(normalize_symbol("I))"), Some("V\u{305}".to_string()));
+/// assert_eq!(normalize_symbol("CI)"), Some("M".to_string()));
+/// assert_eq!(normalize_symbol("."), Some("\u{B7}".to_string()));
+/// assert_eq!(normalize_symbol("IX"), None);
+/// ```
+pub fn normalize_symbol(s: &str) -> Option<String> {
+    let mut chars = s.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if let Some(fraction) = fraction_symbol(c) {
+            return Some(fraction.to_string());
         }
     }
+    match tokenize(s).ok()?.as_slice() {
+        [(_, symbol)] => Some(symbol.to_string()),
+        _ => None,
+    }
 }
 
+/// Returns the canonical numeral for 0 < num < 4000 using the symbols I,V,X,L,C,D,M
+fn standard_numeral(num: u32) -> String {
+    let mut result = "".to_string();
+    let thousands = num / 1000;
+    let hundreds = (num - thousands * 1000) / 100;
+    let tens = (num - thousands * 1000 - hundreds * 100) / 10;
+    let units = num - thousands * 1000 - hundreds * 100 - tens * 10;
+    //todo: refactor
+
+    if let 0..=3 = thousands {
+        for _ in 0..thousands {
+            result.push('M')
+        }
+    }
+
+    match hundreds {
+        0..=3 => {
+            for _ in 0..hundreds {
+                result.push('C')
+            }
+        }
+        4 => result.push_str("CD"),
+        5 => result.push('D'),
+        6 => result.push_str("DC"),
+        7 => result.push_str("DCC"),
+        8 => result.push_str("DCCC"),
+        9 => result.push_str("CM"),
+        _ => {}
+    }
+    match tens {
+        0..=3 => {
+            for _ in 0..tens {
+                result.push('X')
+            }
+        }
+        4 => result.push_str("XL"),
+        5 => result.push('L'),
+        6 => result.push_str("LX"),
+        7 => result.push_str("LXX"),
+        8 => result.push_str("LXXX"),
+        9 => result.push_str("XC"),
+        _ => {}
+    }
+    match units {
+        0..=3 => {
+            for _ in 0..units {
+                result.push('I')
+            }
+        }
+        4 => result.push_str("IV"),
+        5 => result.push('V'),
+        6 => result.push_str("VI"),
+        7 => result.push_str("VII"),
+        8 => result.push_str("VIII"),
+        9 => result.push_str("IX"),
+        _ => {}
+    }
+    result
+}
+
+/// Returns the canonical numeral in vinculum notation, N for zero.
+/// Values from 4000 on are written as overlined thousands followed by the rest,
+/// where I̅ is written as M, e.g. 4000 -> MV̅, 12345 -> X̅MMCCCXLV.
+fn vinculum_numeral(num: u32) -> String {
+    if num == 0 {
+        return NULLA.to_string();
+    }
+    if num < 4000 {
+        return standard_numeral(num);
+    }
+    let mut result = String::new();
+    for c in standard_numeral(num / 1000).chars() {
+        if c == 'I' {
+            result.push('M')
+        } else {
+            result.push(c);
+            result.push(VINCULUM);
+        }
+    }
+    result.push_str(&standard_numeral(num % 1000));
+    result
+}
+
+/// Converts a numeral in vinculum notation to apostrophus notation
+fn to_apostrophus(vinculum: &str) -> String {
+    let mut result = String::new();
+    for (_, symbol) in tokenize(vinculum).unwrap_or_default() {
+        match APOSTROPHUS_GROUPS.iter().find(|(_, v)| *v == symbol) {
+            // D is kept as is, IↃ would be read as part of CIↃ after a C
+            Some((group, _)) if symbol != "D" => result.push_str(group),
+            _ => result.push_str(symbol),
+        }
+    }
+    result
+}
+
+// from integer to Roman numeral
+macro_rules! impl_try_from_int {
+    ($($t:ty),*) => {$(
+        impl TryFrom<$t> for Roman {
+            type Error = RomanRangeError;
+            /// Converts from integer to Roman
+            /// if num is within bounds 0<=num<=3999999, zero is represented as N.
+            /// Values from 4000 on are represented in vinculum notation.
+            /// Returns RomanRangeError for any other value.
+            fn try_from(num: $t) -> Result<Self, Self::Error> {
+                match i32::try_from(num) {
+                    Ok(value) if (0..=MAX_VALUE).contains(&value) => Ok(Roman {
+                        repr: vinculum_numeral(value as u32),
+                        value,
+                    }),
+                    _ => Err(RomanRangeError {
+                        value: num.to_string(),
+                    }),
+                }
+            }
+        }
+    )*};
+}
+
+impl_try_from_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
 impl FromStr for Roman {
     type Err = ParseRomanNumeralError;
     /// Converts from &str to Roman.
@@ -138,11 +684,20 @@ impl FromStr for Roman {
     /// "C" can be subtracted from "D" and "M" only.
     /// "V", "L", and "D" can never be subtracted.
     /// Only one small-value symbol may be subtracted from any large-value symbol.
+    ///
+    /// Values of 4000 and above are written with overlined symbols (vinculum, combining
+    /// character U+0305) that multiply by 1000, e.g. V̅ = 5000, or in apostrophus notation,
+    /// e.g. CIↃ = 1000, IↃↃ = 5000, CCIↃↃ = 10000, where `)` may be used instead of Ↄ.
+    /// The same rules apply to the overlined symbols, I̅ is read as M.
+    ///
+    /// Lowercase letters and the Roman numeral characters of the Unicode Number Forms block
+    /// (U+2160 to U+2188) are accepted as well, ligatures such as Ⅻ stand for their symbols.
+    /// The representation keeps the style of the input.
     /// # Arguments
     /// * `s` - String with Roman numerals
     /// # Example
     /// ```
-    /// use pangalacticcc::roman::{Roman, ParseRomanNumeralError};
+    /// use pangalacticcc::roman::Roman;
     /// let roman_xi: Roman = "XI".parse().unwrap();
     /// assert_eq!(roman_xi.get_value(), 11);
     /// assert_eq!(roman_xi.get_representation(),"XI");
@@ -150,50 +705,69 @@ impl FromStr for Roman {
     ///     Err(e) => e.to_string(),
     ///     _ => "this should not work".to_string()
     /// };
-    /// assert_eq!(wont_work, ParseRomanNumeralError.to_string())
+    /// assert_eq!(wont_work, "invalid roman numeral: symbols are not in canonical order, expected X");
+    /// let roman_big: Roman = "X\u{305}MMCCCXLV".parse().unwrap();
+    /// assert_eq!(roman_big.get_value(), 12345);
+    /// let roman_apostrophus: Roman = "CCI))CI)".parse().unwrap();
+    /// assert_eq!(roman_apostrophus.get_value(), 11000);
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if s.is_empty() {
-            return Err(ParseRomanNumeralError);
+            return Err(ParseRomanNumeralError::new(RomanRule::Empty, None));
         }
         // contains invalid character
-        if !s.chars().all(|c| ROMAN_VALUES.keys().any(|x| x == &c)) {
-            return Err(ParseRomanNumeralError);
+        let tokens = tokenize(s)?;
+        if check_nulla(&tokens)? {
+            return Ok(Roman {
+                repr: apply_style(&NULLA.to_string(), Style::detect(s)),
+                value: 0,
+            });
         }
+        // explain what is wrong with the input if possible
+        check_rules(&tokens)?;
         // a bit hacky...
-        // Perform math on the input characters (e.g. add 10 for X
+        // Perform math on the input symbols (e.g. add 10 for X
         // (or subtract 10 if followed by numeral for larger number, respectively) etc.
-        // Check if result is within representable bounds (0<=result<4000).
+        // Check if result is within representable bounds (0<=result<=MAX_VALUE).
         // If so, convert the result of the calculation to roman numerals
-        // via Roman::from<u32> implemented above
+        // via Roman::try_from<i32> implemented above
         // Check if the original representation matches the newly calculated representation.
         // If they match, the input was valid, otherwise return ParseRomanNumeralError
 
         //convert single numerals to their values
-        let values: Vec<i32> = s.chars().map(|c| ROMAN_VALUES[&c]).collect();
-        let mut sum = 0;
+        let values: Vec<i32> = tokens.iter().map(|(_, t)| symbol_value(t)).collect();
+        let out_of_range = || ParseRomanNumeralError::new(RomanRule::OutOfRange, None);
+        let mut sum: i32 = 0;
         for (i, v) in values.iter().enumerate() {
-            if let Some(next_value) = values.get(i + 1) {
-                if v < next_value {
-                    sum -= v
-                } else {
-                    sum += v
-                }
-            } else {
-                sum += v //last
+            sum = match values.get(i + 1) {
+                Some(next_value) if v < next_value => sum.checked_sub(*v),
+                _ => sum.checked_add(*v),
+            }
+            .ok_or_else(out_of_range)?;
+            // subtracted symbols are followed by larger ones, so the sum never gets smaller again,
+            // long inputs such as repeated C̅M̅ stop here instead of overflowing
+            if sum > MAX_VALUE {
+                return Err(out_of_range());
             }
         }
-        if sum <= 0 || sum >= 4000 {
-            return Err(ParseRomanNumeralError);
-        }
-
-        let valid_numeral_for_result = Roman::from(sum as u32).repr;
-        if valid_numeral_for_result != s {
-            return Err(ParseRomanNumeralError);
+        let valid_numeral_for_result = match Roman::try_from(sum) {
+            Ok(roman) => roman.repr,
+            Err(_) => return Err(ParseRomanNumeralError::new(RomanRule::OutOfRange, None)),
+        };
+        if valid_numeral_for_result != tokens.iter().map(|(_, t)| *t).collect::<String>() {
+            return Err(ParseRomanNumeralError::new(
+                RomanRule::NonCanonical(valid_numeral_for_result),
+                None,
+            ));
         }
 
+        let repr = if uses_apostrophus(s) {
+            to_apostrophus(&valid_numeral_for_result)
+        } else {
+            valid_numeral_for_result
+        };
         Ok(Roman {
-            repr: s.to_string(),
+            repr: apply_style(&repr, Style::detect(s)),
             value: sum,
         })
     }
@@ -208,6 +782,447 @@ impl Roman {
     pub fn get_representation(&self) -> String {
         self.repr.clone()
     }
+    /// Converts from &str to Roman in the given mode,
+    /// see FromStr for Roman and Roman::parse_lenient
+    pub fn parse_with_mode(s: &str, mode: ParseMode) -> Result<Roman, ParseRomanNumeralError> {
+        match mode {
+            ParseMode::Strict => s.parse(),
+            ParseMode::Lenient => Roman::parse_lenient(s),
+        }
+    }
+    /// Converts from &str to Roman, accepting non-canonical numerals as found in
+    /// merchants' ledgers. Runs of the same symbol are subtracted as a whole if followed by
+    /// a larger symbol, otherwise they are added, so symbols may be repeated any number of times.
+    /// The returned Roman holds the canonical representation of the value,
+    /// which can be compared to the input to find out if it was canonical.
+    /// # Arguments
+    /// * `s` - String with Roman numerals
+    /// # Example
+    /// ```
+    /// use pangalacticcc::roman::Roman;
+    /// let roman = Roman::parse_lenient("IIII").unwrap();
+    /// assert_eq!(roman.get_value(), 4);
+    /// assert_eq!(roman.get_representation(), "IV");
+    /// assert_eq!(Roman::parse_lenient("IIX").unwrap().get_representation(), "VIII");
+    /// assert_eq!(Roman::parse_lenient("XIIX").unwrap().get_representation(), "XVIII");
+    /// assert!(Roman::parse_lenient("XYZ").is_err());
+    /// ```
+    pub fn parse_lenient(s: &str) -> Result<Roman, ParseRomanNumeralError> {
+        if s.is_empty() {
+            return Err(ParseRomanNumeralError::new(RomanRule::Empty, None));
+        }
+        let tokens = tokenize(s)?;
+        if check_nulla(&tokens)? {
+            return Ok(Roman {
+                repr: apply_style(&NULLA.to_string(), Style::detect(s)),
+                value: 0,
+            });
+        }
+        // group runs of the same value, e.g. IIX -> [(1, 2), (10, 1)]
+        let mut runs: Vec<(i64, i64)> = Vec::new();
+        for value in tokens.iter().map(|(_, t)| symbol_value(t) as i64) {
+            match runs.last_mut() {
+                Some((v, count)) if *v == value => *count += 1,
+                _ => runs.push((value, 1)),
+            }
+        }
+        let mut sum = 0;
+        for (i, (v, count)) in runs.iter().enumerate() {
+            match runs.get(i + 1) {
+                Some((next_value, _)) if next_value > v => sum -= v * count,
+                _ => sum += v * count,
+            }
+        }
+        // only N stands for zero
+        let canonical = match Roman::try_from(sum) {
+            Ok(roman) if sum > 0 => roman,
+            _ => return Err(ParseRomanNumeralError::new(RomanRule::OutOfRange, None)),
+        };
+        let notation = if uses_apostrophus(s) {
+            Notation::Apostrophus
+        } else {
+            Notation::Vinculum
+        };
+        Ok(Roman {
+            repr: canonical.render(notation, Style::detect(s)),
+            ..canonical
+        })
+    }
+    /// Returns the canonical representation in the given notation
+    /// # Example
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use pangalacticcc::roman::{Notation, Roman};
+    /// let roman = Roman::try_from(15000).unwrap();
+    /// assert_eq!(roman.to_notation(Notation::Vinculum), "X\u{305}V\u{305}");
+    /// assert_eq!(roman.to_notation(Notation::Apostrophus), "CCI\u{2183}\u{2183}I\u{2183}\u{2183}");
+    /// ```
+    pub fn to_notation(&self, notation: Notation) -> String {
+        self.render(notation, Style::Ascii)
+    }
+    /// Returns the canonical representation in the given notation and style,
+    /// e.g. to write numbers the way a document does
+    /// # Example
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use pangalacticcc::roman::{Notation, Roman, Style};
+    /// let roman = Roman::try_from(42).unwrap();
+    /// assert_eq!(roman.render(Notation::Vinculum, Style::Lowercase), "xlii");
+    /// assert_eq!(roman.render(Notation::Vinculum, Style::Unicode), "\u{2169}\u{216C}\u{2160}\u{2160}");
+    /// let roman = Roman::try_from(12).unwrap();
+    /// assert_eq!(roman.render(Notation::Vinculum, Style::UnicodeLowercase), "\u{217B}");
+    /// ```
+    pub fn render(&self, notation: Notation, style: Style) -> String {
+        let vinculum = vinculum_numeral(self.value as u32);
+        let numeral = match notation {
+            Notation::Vinculum => vinculum,
+            Notation::Apostrophus => to_apostrophus(&vinculum),
+        };
+        apply_style(&numeral, style)
+    }
+    /// Returns the single symbols of the canonical representation, e.g. for mapping them
+    /// back to alien numerals
+    /// # Example
+    /// ```
+    /// use std::convert::TryFrom;
+    /// use pangalacticcc::roman::Roman;
+    /// let roman = Roman::try_from(6004).unwrap();
+    /// assert_eq!(roman.symbols(), vec!["V\u{305}", "M", "I", "V"]);
+    /// ```
+    pub fn symbols(&self) -> Vec<String> {
+        // the canonical representation only consists of valid symbols
+        tokenize(&vinculum_numeral(self.value as u32))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, symbol)| symbol.to_string())
+            .collect()
+    }
+    /// Returns an iterator over all Roman numbers within `range`.
+    /// Unbounded ranges start at N (zero) or end at MAX_VALUE, respectively.
+    /// # Example
+    /// ```
+    /// use pangalacticcc::roman::Roman;
+    /// let i: Roman = "I".parse().unwrap();
+    /// let v: Roman = "V".parse().unwrap();
+    /// let numerals = Roman::range(i..=v)
+    ///     .map(|r| r.get_representation())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(numerals, vec!["I", "II", "III", "IV", "V"]);
+    /// ```
+    pub fn range<R: RangeBounds<Roman>>(range: R) -> RomanRange {
+        let next = match range.start_bound() {
+            Bound::Included(r) => r.value,
+            Bound::Excluded(r) => r.value + 1,
+            Bound::Unbounded => 0,
+        };
+        let last = match range.end_bound() {
+            Bound::Included(r) => r.value,
+            Bound::Excluded(r) => r.value - 1,
+            Bound::Unbounded => MAX_VALUE,
+        };
+        RomanRange { next, last }
+    }
+}
+
+impl PartialEq for Roman {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Eq for Roman {}
+
+impl PartialOrd for Roman {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Roman {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl Hash for Roman {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state)
+    }
+}
+
+// checked arithmetic, results outside of 0..=MAX_VALUE are errors
+impl Add for Roman {
+    type Output = Result<Roman, RomanRangeError>;
+    /// # Example
+    /// ```
+    /// use pangalacticcc::roman::Roman;
+    /// let x: Roman = "X".parse().unwrap();
+    /// let v: Roman = "V".parse().unwrap();
+    /// assert_eq!((x + v).unwrap().get_representation(), "XV");
+    /// ```
+    fn add(self, rhs: Self) -> Self::Output {
+        Roman::try_from(self.value as i64 + rhs.value as i64)
+    }
+}
+
+impl Sub for Roman {
+    type Output = Result<Roman, RomanRangeError>;
+    /// Returns RomanRangeError if the result is negative, e.g. X - X is N.
+    fn sub(self, rhs: Self) -> Self::Output {
+        Roman::try_from(self.value as i64 - rhs.value as i64)
+    }
+}
+
+impl Mul for Roman {
+    type Output = Result<Roman, RomanRangeError>;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Roman::try_from(self.value as i64 * rhs.value as i64)
+    }
+}
+
+impl Div for Roman {
+    type Output = Result<Roman, RomanRangeError>;
+    /// Integer division, the remainder is discarded.
+    /// Returns RomanRangeError if the divisor is N (zero).
+    fn div(self, rhs: Self) -> Self::Output {
+        match self.value.checked_div(rhs.value) {
+            Some(value) => Roman::try_from(value),
+            None => Err(RomanRangeError {
+                value: format!("{} / {}", self.repr, rhs.repr),
+            }),
+        }
+    }
+}
+
+impl Sum<Roman> for Result<Roman, RomanRangeError> {
+    /// Sums up all values, returns RomanRangeError if the sum is out of range.
+    /// The sum over an empty iterator is N (zero).
+    /// # Example
+    /// ```
+    /// use pangalacticcc::roman::{Roman, RomanRangeError};
+    /// let numbers = ["X", "X", "I", "I"].iter().map(|s| s.parse::<Roman>().unwrap());
+    /// let total: Result<Roman, RomanRangeError> = numbers.sum();
+    /// assert_eq!(total.unwrap().get_representation(), "XXII");
+    /// ```
+    fn sum<I: Iterator<Item = Roman>>(iter: I) -> Self {
+        Roman::try_from(iter.map(|r| r.value as i64).sum::<i64>())
+    }
+}
+
+impl<'a> Sum<&'a Roman> for Result<Roman, RomanRangeError> {
+    fn sum<I: Iterator<Item = &'a Roman>>(iter: I) -> Self {
+        Roman::try_from(iter.map(|r| r.value as i64).sum::<i64>())
+    }
+}
+
+/// Iterator over consecutive Roman numbers, see Roman::range
+#[derive(Debug, Clone)]
+pub struct RomanRange {
+    /// next value to be returned
+    next: i32,
+    /// last value to be returned
+    last: i32,
+}
+
+impl Iterator for RomanRange {
+    type Item = Roman;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next > self.last {
+            return None;
+        }
+        let roman = Roman::try_from(self.next).ok();
+        self.next += 1;
+        roman
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.last - self.next + 1).max(0) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for RomanRange {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.next > self.last {
+            return None;
+        }
+        let roman = Roman::try_from(self.last).ok();
+        self.last -= 1;
+        roman
+    }
+}
+
+impl ExactSizeIterator for RomanRange {}
+
+/// Represents a Roman number with a fraction in twelfths (unciae),
+/// e.g. IIS·· = 2 + 1/2 + 2/12.
+/// The fraction follows the whole number, S stands for one half (six twelfths)
+/// and each dot · for one twelfth. Fractions below one do not have a whole number, e.g. S·.
+/// # Example
+/// ```
+/// use pangalacticcc::rational::Rational;
+/// use pangalacticcc::roman::RomanFraction;
+/// let half: RomanFraction = "S".parse().unwrap();
+/// assert_eq!(half.to_rational(), Rational::new(1, 2));
+/// let r: RomanFraction = "IIS..".parse().unwrap();
+/// assert_eq!(r.to_rational(), Rational::new(8, 3));
+/// assert_eq!(r.get_representation(), "IIS\u{B7}\u{B7}");
+/// assert!("S·······".parse::<RomanFraction>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RomanFraction {
+    /// whole part, None if the value is below one
+    whole: Option<Roman>,
+    /// fraction in twelfths, 0..=11
+    twelfths: u8,
+}
+
+impl RomanFraction {
+    /// Returns the whole part, None if the value is below one
+    pub fn whole(&self) -> Option<&Roman> {
+        self.whole.as_ref()
+    }
+    /// Returns the fraction in twelfths
+    pub fn twelfths(&self) -> u8 {
+        self.twelfths
+    }
+    /// Returns the exact value
+    pub fn to_rational(&self) -> Rational {
+        let whole = self.whole.as_ref().map_or(0, |w| w.get_value());
+        Rational::from(whole) + Rational::new(self.twelfths as i128, 12)
+    }
+    /// Returns the representation, the whole part as parsed followed by the fraction
+    pub fn get_representation(&self) -> String {
+        let whole = self
+            .whole
+            .as_ref()
+            .map(|w| w.get_representation())
+            .unwrap_or_default();
+        let style = Style::detect(&whole);
+        format!(
+            "{}{}",
+            whole,
+            apply_style(&fraction_numeral(self.twelfths), style)
+        )
+    }
+    /// Returns the canonical representation in the given notation and style,
+    /// see Roman::render
+    pub fn render(&self, notation: Notation, style: Style) -> String {
+        let whole = self
+            .whole
+            .as_ref()
+            .map(|w| w.render(notation, style))
+            .unwrap_or_default();
+        format!(
+            "{}{}",
+            whole,
+            apply_style(&fraction_numeral(self.twelfths), style)
+        )
+    }
+    /// Converts from &str to RomanFraction in the given mode.
+    /// The whole part is parsed like Roman. In strict mode S may be used once and only before
+    /// at most five twelfths. In lenient mode S and twelfths are added up in any order and
+    /// whole numbers are carried over to the whole part.
+    /// # Example
+    /// ```
+    /// use pangalacticcc::rational::Rational;
+    /// use pangalacticcc::roman::{ParseMode, RomanFraction};
+    /// assert!(RomanFraction::parse_with_mode("IS······", ParseMode::Strict).is_err());
+    /// let r = RomanFraction::parse_with_mode("IS······", ParseMode::Lenient).unwrap();
+    /// assert_eq!(r.to_rational(), Rational::from(2));
+    /// assert_eq!(r.get_representation(), "II");
+    /// ```
+    pub fn parse_with_mode(
+        s: &str,
+        mode: ParseMode,
+    ) -> Result<RomanFraction, ParseRomanNumeralError> {
+        if s.is_empty() {
+            return Err(ParseRomanNumeralError::new(RomanRule::Empty, None));
+        }
+        let chars = s.chars().collect::<Vec<_>>();
+        let split = chars
+            .iter()
+            .position(|c| fraction_symbol(*c).is_some())
+            .unwrap_or(chars.len());
+        let whole = match chars[..split].iter().collect::<String>() {
+            w if w.is_empty() => None,
+            w => Some(Roman::parse_with_mode(&w, mode)?),
+        };
+        // N can't be followed by a fraction either
+        if whole.as_ref().map(|w| w.get_value()) == Some(0) && split < chars.len() {
+            return Err(ParseRomanNumeralError::new(
+                RomanRule::NullaNotAlone,
+                Some(0),
+            ));
+        }
+        let mut semis = 0;
+        let mut twelfths = 0;
+        for (i, c) in chars.iter().enumerate().skip(split) {
+            let error = |rule| Err(ParseRomanNumeralError::new(rule, Some(i)));
+            match fraction_symbol(*c) {
+                None => return error(RomanRule::AfterFraction(*c)),
+                Some(_) if mode == ParseMode::Lenient => {}
+                Some(SEMIS) if semis > 0 => {
+                    return error(RomanRule::NeverRepeated(SEMIS.to_string()))
+                }
+                Some(SEMIS) if twelfths > 0 => return error(RomanRule::FractionOrder),
+                Some(UNCIA) if twelfths == 5 => return error(RomanRule::TooManyTwelfths),
+                _ => {}
+            }
+            match fraction_symbol(*c) {
+                Some(SEMIS) => semis += 1,
+                _ => twelfths += 1,
+            }
+        }
+        // only exceeds eleven twelfths in lenient mode
+        let total = semis * 6 + twelfths;
+        let whole = match (whole, total / 12) {
+            (whole, 0) => whole,
+            (whole, carry) => {
+                let value = whole.map_or(0, |w| w.get_value()) + carry;
+                match Roman::try_from(value) {
+                    Ok(roman) => Some(Roman {
+                        repr: roman.render(Notation::Vinculum, Style::detect(s)),
+                        ..roman
+                    }),
+                    Err(_) => return Err(ParseRomanNumeralError::new(RomanRule::OutOfRange, None)),
+                }
+            }
+        };
+        Ok(RomanFraction {
+            whole,
+            twelfths: (total % 12) as u8,
+        })
+    }
+}
+
+impl FromStr for RomanFraction {
+    type Err = ParseRomanNumeralError;
+    /// Converts from &str to RomanFraction, see RomanFraction::parse_with_mode
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RomanFraction::parse_with_mode(s, ParseMode::Strict)
+    }
+}
+
+impl From<Roman> for RomanFraction {
+    fn from(roman: Roman) -> Self {
+        RomanFraction {
+            whole: Some(roman),
+            twelfths: 0,
+        }
+    }
+}
+
+/// Returns the canonical fraction for 0 <= twelfths < 12, e.g. S·· for 8
+fn fraction_numeral(twelfths: u8) -> String {
+    let mut result = String::new();
+    if twelfths >= 6 {
+        result.push(SEMIS);
+    }
+    for _ in 0..twelfths % 6 {
+        result.push(UNCIA);
+    }
+    result
 }
 
 #[cfg(test)]
@@ -217,39 +1232,75 @@ mod tests {
     /* uint to Roman */
     #[test]
     fn test_int_to_roman_1000() {
-        assert_eq!(Roman::from(1000).repr, "M")
+        assert_eq!(Roman::try_from(1000).unwrap().repr, "M")
     }
     #[test]
     fn test_int_to_roman_900() {
-        assert_eq!(Roman::from(900).repr, "CM")
+        assert_eq!(Roman::try_from(900).unwrap().repr, "CM")
     }
     #[test]
     fn test_int_to_roman_3() {
-        assert_eq!(Roman::from(3).repr, "III")
+        assert_eq!(Roman::try_from(3).unwrap().repr, "III")
     }
     #[test]
     fn test_int_to_roman_1903() {
-        assert_eq!(Roman::from(1903).repr, "MCMIII")
+        assert_eq!(Roman::try_from(1903).unwrap().repr, "MCMIII")
     }
     #[test]
-    #[should_panic]
     fn test_int_to_roman_4000() {
-        let _that_wont_work = Roman::from(4000);
+        assert_eq!(Roman::try_from(4000).unwrap().repr, "MV\u{305}")
+    }
+    #[test]
+    fn test_int_to_roman_3999999() {
+        assert_eq!(
+            Roman::try_from(3_999_999).unwrap().repr,
+            "M\u{305}M\u{305}M\u{305}C\u{305}M\u{305}X\u{305}C\u{305}MX\u{305}CMXCIX"
+        )
+    }
+    #[test]
+    fn test_int_to_roman_4000000_err() {
+        let result = Roman::try_from(4_000_000u32);
+        match result {
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "4000000 cannot be converted. Hint: Only values from 0 to 3999999 can be represented."
+            ),
+            _ => panic!("this should be an error"),
+        }
+    }
+    #[test]
+    fn test_int_to_roman_0_nulla() {
+        let zero = Roman::try_from(0u8).unwrap();
+        assert_eq!(zero.get_value(), 0);
+        assert_eq!(zero.get_representation(), "N");
+    }
+    #[test]
+    fn test_int_to_roman_negative_err() {
+        assert!(Roman::try_from(-5i64).is_err());
+    }
+    #[test]
+    fn test_int_to_roman_widths() {
+        assert_eq!(Roman::try_from(42u8).unwrap().repr, "XLII");
+        assert_eq!(Roman::try_from(42u16).unwrap().repr, "XLII");
+        assert_eq!(Roman::try_from(42i64).unwrap().repr, "XLII");
+        assert_eq!(Roman::try_from(42usize).unwrap().repr, "XLII");
+        assert!(Roman::try_from(u128::MAX).is_err());
+        assert!(Roman::try_from(i128::MIN).is_err());
     }
     #[test]
     fn test_int_to_roman_3999() {
-        assert_eq!(Roman::from(3999).repr, "MMMCMXCIX")
+        assert_eq!(Roman::try_from(3999).unwrap().repr, "MMMCMXCIX")
     }
     #[test]
     fn test_int_to_roman_42() {
-        assert_eq!(Roman::from(42).repr, "XLII")
+        assert_eq!(Roman::try_from(42).unwrap().repr, "XLII")
     }
     /* Roman to int */
     #[test]
     fn test_roman_invalid_strings_raise_error() {
         let result = "".parse::<Roman>();
         match result {
-            Err(e) => assert_eq!(e.to_string(), "invalid string for roman numeral"),
+            Err(e) => assert_eq!(e.to_string(), "invalid roman numeral: empty numeral"),
             _ => panic!("this should be an error"),
         }
     }
@@ -257,7 +1308,10 @@ mod tests {
     fn test_roman_invalid_strings_raise_error_y() {
         let result = "Y".parse::<Roman>();
         match result {
-            Err(e) => assert_eq!(e.to_string(), "invalid string for roman numeral"),
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "invalid roman numeral: Y is not a roman symbol (at 0)"
+            ),
             _ => panic!("this should be an error"),
         }
     }
@@ -290,7 +1344,10 @@ mod tests {
     fn test_roman_mmmm_err() {
         let result = "MMMM".parse::<Roman>();
         match result {
-            Err(e) => assert_eq!(e, ParseRomanNumeralError),
+            Err(e) => assert_eq!(
+                e,
+                ParseRomanNumeralError::new(RomanRule::TooManyRepeats("M".to_string()), Some(3))
+            ),
             _ => panic!("this should not be Ok"),
         }
     }
@@ -323,7 +1380,13 @@ mod tests {
     fn test_roman_mmmccm_err() {
         let result = "MMMCCM".parse::<Roman>();
         match result {
-            Err(e) => assert_eq!(e, ParseRomanNumeralError),
+            Err(e) => assert_eq!(
+                e,
+                ParseRomanNumeralError::new(
+                    RomanRule::MultipleSubtraction("C".to_string(), "M".to_string()),
+                    Some(3)
+                )
+            ),
             _ => panic!("this should not be Ok"),
         }
     }
@@ -347,7 +1410,10 @@ mod tests {
     fn test_roman_cccc_err() {
         let result = "CCCC".parse::<Roman>();
         match result {
-            Err(e) => assert_eq!(e, ParseRomanNumeralError),
+            Err(e) => assert_eq!(
+                e,
+                ParseRomanNumeralError::new(RomanRule::TooManyRepeats("C".to_string()), Some(3))
+            ),
             _ => panic!("this should not be Ok"),
         }
     }
@@ -355,7 +1421,10 @@ mod tests {
     fn test_roman_cmcd_err() {
         let result = "CMCD".parse::<Roman>();
         match result {
-            Err(e) => assert_eq!(e, ParseRomanNumeralError),
+            Err(e) => assert_eq!(
+                e,
+                ParseRomanNumeralError::new(RomanRule::NonCanonical("MCCC".to_string()), None)
+            ),
             _ => panic!("this should not be Ok"),
         }
     }
@@ -401,4 +1470,428 @@ mod tests {
             _ => panic!("this should be Ok"),
         }
     }
+
+    /* extended notation */
+    #[test]
+    fn test_roman_vinculum_ok() {
+        let result = "V\u{305}MMCCCXLV".parse::<Roman>();
+        match result {
+            Ok(r) => assert_eq!(r.value, 7345),
+            _ => panic!("this should be Ok"),
+        }
+        let result = "I\u{305}V\u{305}".parse::<Roman>();
+        match result {
+            Ok(r) => assert_eq!(r.value, 4000),
+            _ => panic!("this should be Ok"),
+        }
+    }
+    #[test]
+    fn test_roman_vinculum_err() {
+        // V̅ can never be repeated
+        let result = "V\u{305}V\u{305}".parse::<Roman>();
+        match result {
+            Err(e) => assert_eq!(
+                e,
+                ParseRomanNumeralError::new(
+                    RomanRule::NeverRepeated("V\u{305}".to_string()),
+                    Some(2)
+                )
+            ),
+            _ => panic!("this should not be Ok"),
+        }
+        // overline without symbol
+        let result = "\u{305}X".parse::<Roman>();
+        match result {
+            Err(e) => assert_eq!(
+                e,
+                ParseRomanNumeralError::new(RomanRule::InvalidSymbol('\u{305}'), Some(0))
+            ),
+            _ => panic!("this should not be Ok"),
+        }
+    }
+    #[test]
+    fn test_roman_apostrophus_ok() {
+        let result = "CCI\u{2183}\u{2183}I\u{2183}\u{2183}".parse::<Roman>();
+        match result {
+            Ok(r) => assert_eq!(r.value, 15000),
+            _ => panic!("this should be Ok"),
+        }
+        let result = "CI)CI)".parse::<Roman>();
+        match result {
+            Ok(r) => {
+                assert_eq!(r.value, 2000);
+                assert_eq!(r.repr, "CI\u{2183}CI\u{2183}")
+            }
+            _ => panic!("this should be Ok"),
+        }
+        // C before CIↃ is subtracted like C before M
+        let result = "CCI)".parse::<Roman>();
+        match result {
+            Ok(r) => assert_eq!(r.value, 900),
+            _ => panic!("this should be Ok"),
+        }
+    }
+    #[test]
+    fn test_roman_extended_round_trip() {
+        for value in [4000, 4999, 12345, 400_000, 1_234_567, 3_999_999] {
+            let roman = Roman::try_from(value).unwrap();
+            for notation in [Notation::Vinculum, Notation::Apostrophus] {
+                let parsed = roman.to_notation(notation).parse::<Roman>().unwrap();
+                assert_eq!(parsed.value, value)
+            }
+        }
+    }
+    #[test]
+    fn test_normalize_symbol() {
+        assert_eq!(normalize_symbol("I\u{305}"), Some("M".to_string()));
+        assert_eq!(normalize_symbol("CCI))"), Some("X\u{305}".to_string()));
+        assert_eq!(normalize_symbol("I)"), Some("D".to_string()));
+        assert_eq!(normalize_symbol("VV"), None);
+        assert_eq!(normalize_symbol("Y"), None);
+    }
+
+    /* arithmetic and ordering */
+    fn roman(s: &str) -> Roman {
+        s.parse().unwrap()
+    }
+    #[test]
+    fn test_roman_eq_across_notations() {
+        assert_eq!(roman("MV\u{305}"), roman("CI)I))"));
+        assert_ne!(roman("X"), roman("XI"));
+    }
+    #[test]
+    fn test_roman_ord() {
+        assert!(roman("IX") < roman("X"));
+        assert!(roman("V\u{305}") > roman("MMMCMXCIX"));
+        let mut numbers = vec![roman("L"), roman("IV"), roman("XL")];
+        numbers.sort();
+        assert_eq!(numbers, vec![roman("IV"), roman("XL"), roman("L")]);
+    }
+    #[test]
+    fn test_roman_hash() {
+        let mut set = std::collections::HashSet::new();
+        set.insert(roman("CI)"));
+        assert!(set.contains(&roman("M")));
+    }
+    #[test]
+    fn test_roman_add_sub_mul_div() {
+        assert_eq!((roman("XL") + roman("II")).unwrap().repr, "XLII");
+        assert_eq!((roman("XL") - roman("II")).unwrap().repr, "XXXVIII");
+        assert_eq!((roman("XL") * roman("C")).unwrap().repr, "MV\u{305}");
+        assert_eq!((roman("XL") / roman("III")).unwrap().repr, "XIII");
+    }
+    #[test]
+    fn test_roman_arithmetic_out_of_range() {
+        assert!((roman("II") - roman("X")).is_err());
+        assert!((roman("I") / roman("N")).is_err());
+        assert!((roman("M\u{305}M\u{305}") * roman("II")).is_err());
+        assert!((roman("M\u{305}M\u{305}") + roman("M\u{305}M\u{305}")).is_err());
+    }
+    #[test]
+    fn test_roman_sum() {
+        let numbers = [roman("X"), roman("X"), roman("I"), roman("I")];
+        let total: Result<Roman, RomanRangeError> = numbers.iter().sum();
+        assert_eq!(total.unwrap(), roman("XXII"));
+        let empty: Result<Roman, RomanRangeError> = Vec::<Roman>::new().into_iter().sum();
+        assert_eq!(empty.unwrap(), roman("N"));
+    }
+    #[test]
+    fn test_roman_range() {
+        let numbers = Roman::range(roman("VIII")..roman("XI"));
+        assert_eq!(numbers.len(), 3);
+        let numerals = numbers.map(|r| r.repr).collect::<Vec<_>>();
+        assert_eq!(numerals, vec!["VIII", "IX", "X"]);
+        let last = Roman::range(roman("MMMCMXCIX")..).next_back().unwrap();
+        assert_eq!(last.value, MAX_VALUE);
+        assert_eq!(Roman::range(..=roman("III")).count(), 4);
+        let numerals = Roman::range(..=roman("V"))
+            .map(|r| r.repr)
+            .collect::<Vec<_>>();
+        assert_eq!(numerals, vec!["N", "I", "II", "III", "IV", "V"]);
+        assert_eq!(Roman::range(roman("X")..roman("V")).count(), 0);
+    }
+
+    /* lenient parsing */
+    #[test]
+    fn test_roman_lenient_additive() {
+        for (input, value, canonical) in [
+            ("IIII", 4, "IV"),
+            ("IIX", 8, "VIII"),
+            ("XIIX", 18, "XVIII"),
+            ("XXXXVIIII", 49, "XLIX"),
+            ("MDCCCCX", 1910, "MCMX"),
+            ("IV", 4, "IV"),
+        ] {
+            let roman = Roman::parse_lenient(input).unwrap();
+            assert_eq!(roman.value, value);
+            assert_eq!(roman.repr, canonical);
+        }
+    }
+    #[test]
+    fn test_roman_lenient_err() {
+        assert!(Roman::parse_lenient("").is_err());
+        assert!(Roman::parse_lenient("IIIIIIIIIIX").is_err()); // 10 - 10 = 0
+        assert!(Roman::parse_lenient("Y").is_err());
+    }
+    #[test]
+    fn test_roman_parse_with_mode() {
+        assert!(Roman::parse_with_mode("IIII", ParseMode::Strict).is_err());
+        assert_eq!(
+            Roman::parse_with_mode("IIII", ParseMode::Lenient)
+                .unwrap()
+                .value,
+            4
+        );
+    }
+
+    /* explanations */
+    fn rule_of(s: &str) -> (RomanRule, Option<usize>) {
+        let e = s.parse::<Roman>().unwrap_err();
+        (e.rule().clone(), e.position())
+    }
+    #[test]
+    fn test_roman_err_never_subtracted() {
+        assert_eq!(
+            rule_of("VL"),
+            (RomanRule::NeverSubtracted("V".to_string()), Some(0))
+        );
+        assert_eq!(
+            rule_of("MDM"),
+            (RomanRule::NeverSubtracted("D".to_string()), Some(1))
+        );
+    }
+    #[test]
+    fn test_roman_err_invalid_subtraction() {
+        assert_eq!(
+            rule_of("IC"),
+            (
+                RomanRule::InvalidSubtraction("I".to_string(), "C".to_string()),
+                Some(0)
+            )
+        );
+        assert_eq!(
+            rule_of("XM"),
+            (
+                RomanRule::InvalidSubtraction("X".to_string(), "M".to_string()),
+                Some(0)
+            )
+        );
+    }
+    #[test]
+    fn test_roman_err_multiple_subtraction() {
+        assert_eq!(
+            rule_of("XIIX"),
+            (
+                RomanRule::MultipleSubtraction("I".to_string(), "X".to_string()),
+                Some(1)
+            )
+        );
+    }
+    #[test]
+    fn test_roman_err_repeats() {
+        assert_eq!(
+            rule_of("XIIII"),
+            (RomanRule::TooManyRepeats("I".to_string()), Some(4))
+        );
+        assert_eq!(
+            rule_of("LL"),
+            (RomanRule::NeverRepeated("L".to_string()), Some(1))
+        );
+        // XXXIX is fine, the fourth X is separated by I
+        assert!("XXXIX".parse::<Roman>().is_ok());
+    }
+    #[test]
+    fn test_roman_err_out_of_range() {
+        assert_eq!(
+            rule_of("M\u{305}M\u{305}M\u{305}M\u{305}"),
+            (RomanRule::TooManyRepeats("M\u{305}".to_string()), Some(6))
+        );
+        assert_eq!(
+            Roman::parse_lenient("M\u{305}M\u{305}M\u{305}M\u{305}")
+                .unwrap_err()
+                .rule(),
+            &RomanRule::OutOfRange
+        );
+    }
+    #[test]
+    fn test_roman_err_out_of_range_long_input() {
+        // repeated subtractive pairs pass the rules, the sum must not overflow
+        let long = "C\u{305}M\u{305}".repeat(2400);
+        assert_eq!(
+            long.parse::<Roman>().unwrap_err().rule(),
+            &RomanRule::OutOfRange
+        );
+        assert_eq!(
+            Roman::parse_lenient(&long).unwrap_err().rule(),
+            &RomanRule::OutOfRange
+        );
+    }
+    #[test]
+    fn test_roman_err_display() {
+        let e = "XIIX".parse::<Roman>().unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "invalid roman numeral: only one I may be subtracted from X (at 1)"
+        );
+    }
+    #[test]
+    fn test_roman_lowercase() {
+        let roman: Roman = "mcmiii".parse().unwrap();
+        assert_eq!(roman.get_value(), 1903);
+        assert_eq!(roman.get_representation(), "mcmiii");
+        assert_eq!("xC".parse::<Roman>().unwrap().get_value(), 90);
+        assert!("iiii".parse::<Roman>().is_err());
+    }
+    #[test]
+    fn test_roman_unicode() {
+        // ⅯⅭⅯⅢ
+        let roman: Roman = "\u{216F}\u{216D}\u{216F}\u{2162}".parse().unwrap();
+        assert_eq!(roman.get_value(), 1903);
+        assert_eq!(
+            roman.get_representation(),
+            "\u{216F}\u{216D}\u{216F}\u{2160}\u{2160}\u{2160}"
+        );
+        // ⅹⅼⅱ
+        assert_eq!(
+            "\u{2179}\u{217C}\u{2171}"
+                .parse::<Roman>()
+                .unwrap()
+                .get_value(),
+            42
+        );
+        // ↂ, ↁ, ↀ
+        assert_eq!("\u{2182}".parse::<Roman>().unwrap().get_value(), 10000);
+        assert_eq!(
+            "\u{2181}\u{2180}".parse::<Roman>().unwrap().get_value(),
+            6000
+        );
+    }
+    #[test]
+    fn test_roman_unicode_ligatures() {
+        for (i, c) in ('\u{2160}'..='\u{216B}').enumerate() {
+            let roman: Roman = c.to_string().parse().unwrap();
+            assert_eq!(roman.get_value(), i as i32 + 1);
+            assert_eq!(roman.get_representation(), c.to_string());
+        }
+        for (i, c) in ('\u{2170}'..='\u{217B}').enumerate() {
+            assert_eq!(
+                Roman::parse_lenient(&c.to_string()).unwrap().get_value(),
+                i as i32 + 1
+            );
+        }
+        // Ⅻ followed by Ⅼ is XIIL, which is not canonical
+        assert!("\u{216B}\u{216C}".parse::<Roman>().is_err());
+    }
+    #[test]
+    fn test_roman_unicode_error_position() {
+        // ⅩⅫⅩ, the error is in the ligature at index 1
+        let e = "\u{2169}\u{216B}\u{2169}".parse::<Roman>().unwrap_err();
+        assert_eq!(e.position(), Some(1));
+        let e = "\u{216B}z".parse::<Roman>().unwrap_err();
+        assert_eq!(e.rule(), &RomanRule::InvalidSymbol('z'));
+        assert_eq!(e.position(), Some(1));
+    }
+    #[test]
+    fn test_roman_render_round_trip_styles() {
+        let styles = [
+            Style::Ascii,
+            Style::Lowercase,
+            Style::Unicode,
+            Style::UnicodeLowercase,
+        ];
+        for value in [1, 4, 12, 13, 49, 1903, 5000, 123_456] {
+            let roman = Roman::try_from(value).unwrap();
+            for notation in [Notation::Vinculum, Notation::Apostrophus] {
+                for style in styles {
+                    let rendered = roman.render(notation, style);
+                    let parsed: Roman = rendered.parse().unwrap();
+                    assert_eq!(parsed, roman);
+                    assert_eq!(parsed.get_representation(), rendered);
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_roman_lowercase_symbol() {
+        assert_eq!(normalize_symbol("x"), Some("X".to_string()));
+        assert_eq!(normalize_symbol("\u{216E}"), Some("D".to_string()));
+        assert_eq!(normalize_symbol("\u{216B}"), None);
+    }
+    #[test]
+    fn test_roman_fraction_values() {
+        let value = |s: &str| s.parse::<RomanFraction>().unwrap().to_rational();
+        assert_eq!(value("S"), Rational::new(1, 2));
+        assert_eq!(value("\u{B7}"), Rational::new(1, 12));
+        assert_eq!(
+            value("S\u{B7}\u{B7}\u{B7}\u{B7}\u{B7}"),
+            Rational::new(11, 12)
+        );
+        assert_eq!(value("XLIIS"), Rational::new(85, 2));
+        assert_eq!(value("ii\u{2022}\u{2022}\u{2022}"), Rational::new(9, 4));
+        assert_eq!(value("XII"), Rational::from(12));
+    }
+    #[test]
+    fn test_roman_fraction_errors() {
+        let rule_of = |s: &str| {
+            let e = s.parse::<RomanFraction>().unwrap_err();
+            (e.rule().clone(), e.position())
+        };
+        assert_eq!(rule_of(""), (RomanRule::Empty, None));
+        assert_eq!(rule_of("SI"), (RomanRule::AfterFraction('I'), Some(1)));
+        assert_eq!(
+            rule_of("ISS"),
+            (RomanRule::NeverRepeated("S".to_string()), Some(2))
+        );
+        assert_eq!(rule_of("I.S"), (RomanRule::FractionOrder, Some(2)));
+        assert_eq!(rule_of("......"), (RomanRule::TooManyTwelfths, Some(5)));
+        assert_eq!(
+            rule_of("IIIIS"),
+            (RomanRule::TooManyRepeats("I".to_string()), Some(3))
+        );
+    }
+    #[test]
+    fn test_roman_fraction_lenient_carry() {
+        let r = RomanFraction::parse_with_mode(".S.S.....", ParseMode::Lenient).unwrap();
+        assert_eq!(r.to_rational(), Rational::new(19, 12));
+        assert_eq!(r.get_representation(), "IS\u{B7}");
+        let r = RomanFraction::parse_with_mode("SS", ParseMode::Lenient).unwrap();
+        assert_eq!(r.whole().map(|w| w.get_value()), Some(1));
+        assert_eq!(r.twelfths(), 0);
+    }
+    #[test]
+    fn test_roman_fraction_render() {
+        let r: RomanFraction = "xs..".parse().unwrap();
+        assert_eq!(r.get_representation(), "xs\u{B7}\u{B7}");
+        assert_eq!(r.render(Notation::Vinculum, Style::Ascii), "XS\u{B7}\u{B7}");
+        assert_eq!(
+            RomanFraction::from(Roman::try_from(3).unwrap()).twelfths(),
+            0
+        );
+    }
+    #[test]
+    fn test_roman_nulla() {
+        assert_eq!(roman("N").get_value(), 0);
+        assert_eq!("n".parse::<Roman>().unwrap().get_representation(), "n");
+        assert_eq!(Roman::parse_lenient("N").unwrap().get_value(), 0);
+        assert_eq!((roman("X") - roman("X")).unwrap(), roman("N"));
+        assert_eq!((roman("I") / roman("II")).unwrap(), roman("N"));
+        assert_eq!(Roman::try_from(0).unwrap().symbols(), vec!["N"]);
+    }
+    #[test]
+    fn test_roman_nulla_not_alone() {
+        for (s, position) in [("NI", 0), ("XN", 1), ("NN", 0)] {
+            let e = s.parse::<Roman>().unwrap_err();
+            assert_eq!(e.rule(), &RomanRule::NullaNotAlone);
+            assert_eq!(e.position(), Some(position));
+        }
+        assert!(Roman::parse_lenient("IN").is_err());
+        assert_eq!(
+            "NS".parse::<RomanFraction>().unwrap_err().rule(),
+            &RomanRule::NullaNotAlone
+        );
+        assert_eq!(
+            "N".parse::<RomanFraction>().unwrap().to_rational(),
+            Rational::from(0)
+        );
+    }
 }
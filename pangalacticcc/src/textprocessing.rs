@@ -1,5 +1,7 @@
-use crate::{PccResult, Roman};
-use regex::Regex;
+use crate::numerals::{NumeralSystem, RomanNumerals};
+use crate::parser::{parse_sentence, tokenize, Statement, Token, TokenKind};
+use crate::rational::{OverflowError, ParseRationalError, Rational};
+use crate::PccResult;
 use std::collections::HashMap;
 use std::error;
 use std::fmt::{Display, Formatter};
@@ -25,15 +27,144 @@ impl Display for MapAlienNumeralError {
     }
 }
 
+/// Occurs when the price of a unit should be derived from an amount of zero units
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZeroAmountError;
+impl error::Error for ZeroAmountError {}
+impl Display for ZeroAmountError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "price can not be derived from zero units")
+    }
+}
+
+/// Occurs when one unit of a currency should be worth nothing in another currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZeroExchangeRateError;
+impl error::Error for ZeroExchangeRateError {}
+impl Display for ZeroExchangeRateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "exchange rate can not be zero")
+    }
+}
+
+/// Returns `sentence` without surrounding whitespace and with every run of whitespace
+/// (spaces, tabs, ...) replaced by a single space.
+/// # Example
+/// ```
+/// use pangalacticcc::textprocessing::normalize_whitespace;
+/// assert_eq!(normalize_whitespace("  glob \t glob  Silver is 34 Credits "), "glob glob Silver is 34 Credits");
+/// ```
+pub fn normalize_whitespace(sentence: &str) -> String {
+    sentence.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Returns the name of a currency in a form that is the same for every spelling of it:
+/// currencies are case-insensitive and a trailing "s" is ignored,
+/// so "Credits", "credit" and "CREDITS" are the same currency.
+/// # Example
+/// ```
+/// use pangalacticcc::textprocessing::normalize_currency;
+/// assert_eq!(normalize_currency("Zorkmids"), normalize_currency("zorkmid"));
+/// assert_eq!(normalize_currency("s"), "s");
+/// ```
+pub fn normalize_currency(name: &str) -> String {
+    let name = name.to_lowercase();
+    match name.strip_suffix('s') {
+        Some(singular) if !singular.is_empty() => singular.to_string(),
+        _ => name,
+    }
+}
+
+/// Returns the value stored for an alien word, i.e. an alien numeral or a unit name.
+/// Alien words are case-insensitive and stored in lowercase, see KnowledgeBase,
+/// so "Glob" finds the value stored for "glob".
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use pangalacticcc::textprocessing::get_ignore_case;
+/// let mut nm: HashMap<String, String> = HashMap::new();
+/// nm.insert("glob".to_string(), "I".to_string());
+/// assert_eq!(get_ignore_case(&nm, "GLOB"), Some(&"I".to_string()));
+/// assert_eq!(get_ignore_case(&nm, "prok"), None);
+/// ```
+pub fn get_ignore_case<'a, V>(map: &'a HashMap<String, V>, word: &str) -> Option<&'a V> {
+    map.get(&word.to_lowercase())
+}
+
+/// Splits the words of an amount followed by a unit into alien numerals and the unit.
+/// Leading words are numerals as long as they are found in `numeral_map`, the rest is the unit,
+/// so units may consist of several words. A unit in double quotes is taken as is.
+/// Words are separated by any amount of whitespace.
+/// Returns None if there is no unit or the quotes are not closed at the end.
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use pangalacticcc::textprocessing::split_amount_and_unit;
+/// let mut nm: HashMap<String, String> = HashMap::new();
+/// nm.insert("glob".to_string(), "I".to_string());
+/// assert_eq!(
+///     split_amount_and_unit(&nm, "glob glob Dark Matter"),
+///     Some((vec!["glob", "glob"], "Dark Matter".to_string()))
+/// );
+/// assert_eq!(
+///     split_amount_and_unit(&nm, "glob \"glob Dust\""),
+///     Some((vec!["glob"], "glob Dust".to_string()))
+/// );
+/// assert_eq!(split_amount_and_unit(&nm, "glob glob"), None);
+/// ```
+pub fn split_amount_and_unit<'a>(
+    numeral_map: &HashMap<String, String>,
+    text: &'a str,
+) -> Option<(Vec<&'a str>, String)> {
+    let tokens = tokenize(text).ok()?;
+    let (amount, unit) = split_amount_and_unit_tokens(numeral_map, &tokens)?;
+    Some((
+        amount.iter().map(|t| &text[t.span().range()]).collect(),
+        unit,
+    ))
+}
+
+/// Splits the tokens of an amount followed by a unit into alien numerals and the unit,
+/// see split_amount_and_unit. A quoted unit has to be the last token.
+pub fn split_amount_and_unit_tokens<'a>(
+    numeral_map: &HashMap<String, String>,
+    tokens: &'a [Token],
+) -> Option<(&'a [Token], String)> {
+    if let Some(quoted) = tokens.iter().position(|t| t.kind() == TokenKind::Quoted) {
+        let unit = normalize_whitespace(tokens[quoted].text());
+        if unit.is_empty() || quoted != tokens.len() - 1 {
+            return None;
+        }
+        return Some((&tokens[..quoted], unit));
+    }
+    let numerals = tokens
+        .iter()
+        .take_while(|t| get_ignore_case(numeral_map, t.text()).is_some())
+        .count();
+    if numerals == tokens.len() {
+        return None;
+    }
+    let unit = tokens[numerals..]
+        .iter()
+        .map(|t| t.text())
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some((&tokens[..numerals], unit))
+}
+
 /// Returns true if questions asks how much is
 /// # Example
 /// ```
 /// use pangalacticcc::textprocessing::is_question_how_much;
-/// assert_eq!(is_question_how_much("How much is da Fish ?"), true)
+/// assert_eq!(is_question_how_much("How much is da Fish ?"), true);
+/// assert_eq!(is_question_how_much("how  MUCH\tis da Fish?"), true);
 /// ```
 pub fn is_question_how_much(sentence: &str) -> bool {
     // example: how much is pish tegj glob glob ?
-    sentence.to_lowercase().starts_with("how much is") && sentence.ends_with('?')
+    matches!(
+        parse_sentence(sentence).statement(),
+        Statement::HowMuch { .. }
+    )
 }
 
 /// Returns true if questions asks for how many credits, and an alien numeral can be extracted
@@ -42,23 +173,31 @@ pub fn is_question_how_much(sentence: &str) -> bool {
 /// use pangalacticcc::textprocessing::is_question_how_many_credits;
 /// assert_eq!(is_question_how_many_credits("how many Credits is glob prok Silver ?"), true);
 /// assert_eq!(is_question_how_many_credits("How many Credits is da da da Fish ?"), true);
+/// assert_eq!(is_question_how_many_credits("How Many  credits is da Fish?"), true);
 /// ```
-
 pub fn is_question_how_many_credits(sentence: &str) -> bool {
-    sentence.to_lowercase().starts_with("how many credits is") && sentence.ends_with('?')
+    match parse_sentence(sentence).statement() {
+        Statement::HowMany { target, .. } => match target.as_slice() {
+            [currency] => normalize_currency(currency.text()) == normalize_currency("Credits"),
+            _ => false,
+        },
+        _ => false,
+    }
 }
 
-/// Returns true if sentence is statement about units, and a number of Credits can be extracted
+/// Returns true if sentence is statement about units, and an amount of a currency can be extracted
 /// # Example
 /// ```
 /// use pangalacticcc::textprocessing::is_unit_info;
 /// assert_eq!(is_unit_info("glob prok Gold is 57800 Credits"), true);
 /// assert_eq!(is_unit_info("Da da da Fish is 3 Credits"), true);
+/// assert_eq!(is_unit_info("glob Silver is 3 Zorkmids"), true);
+/// assert_eq!(is_unit_info("glob \"Dark Matter\" is 3 Credits"), true);
 /// assert_eq!(is_unit_info("Da da da Fish is three Credits"), false);
-
+/// assert_eq!(is_unit_info("Fish is 3 Credits"), false);
 /// ```
 pub fn is_unit_info(sentence: &str) -> bool {
-    extract_units_from_sentence(sentence).is_ok()
+    matches!(parse_sentence(sentence).statement(), Statement::Unit { .. })
 }
 
 /// Returns true if sentence is statement about numerals
@@ -67,194 +206,373 @@ pub fn is_unit_info(sentence: &str) -> bool {
 /// use pangalacticcc::textprocessing::is_numeral_info;
 /// assert_eq!(is_numeral_info("da is I"), true);
 /// assert_eq!(is_numeral_info("pish is X"), true);
-/// assert_eq!(is_numeral_info("pish is IX"), false);
+/// assert_eq!(is_numeral_info("pish is IX"), true);
+/// assert_eq!(is_numeral_info("pish is IIX"), false);
 /// assert_eq!(is_numeral_info("pish is A"), false);
 /// ```
 pub fn is_numeral_info(sentence: &str) -> bool {
     if sentence.is_empty() {
         return false;
     }
-    numerals_to_roman(sentence) != None
+    numerals_to_roman(sentence).is_some()
 }
 
 /// Returns a Result with the unit extracted from a sentence about unit info or ParseSentenceError if extraction failed.
-/// Sentences are expected to have an amount stated directly before the unit.
+/// Sentences are expected to have an amount stated directly before the unit,
+/// see split_amount_and_unit.
 /// # Example
 /// ```
+/// use std::collections::HashMap;
 /// use pangalacticcc::textprocessing::{extract_units_from_sentence, ParseSentenceError};
-/// assert_eq!(extract_units_from_sentence("glob prok Iron is 782 Credits").unwrap(),"Iron".to_string());
-/// assert_eq!(extract_units_from_sentence("Iron is 10 Credits").unwrap_err().to_string(), ParseSentenceError.to_string());
+/// let mut nm: HashMap<String, String> = HashMap::new();
+/// nm.insert("glob".to_string(), "I".to_string());
+/// nm.insert("prok".to_string(), "V".to_string());
+/// assert_eq!(extract_units_from_sentence(&nm, "glob prok Iron is 782 Credits").unwrap(),"Iron".to_string());
+/// assert_eq!(extract_units_from_sentence(&nm, "glob Dark Matter is 782 Credits").unwrap(),"Dark Matter".to_string());
+/// assert_eq!(extract_units_from_sentence(&nm, "Iron is 10 Credits").unwrap_err().to_string(), ParseSentenceError.to_string());
 /// ```
-pub fn extract_units_from_sentence(sentence: &str) -> PccResult<String> {
+pub fn extract_units_from_sentence(
+    numeral_map: &HashMap<String, String>,
+    sentence: &str,
+) -> PccResult<String> {
     // assuming Credits is agreed upon
-    let unit_regex = Regex::new(r"^([\w ]+) is (\d+) Credits$").unwrap();
-    if let Some(captures) = unit_regex.captures(sentence.trim_start().trim_end()) {
-        let result = captures
-            .iter()
-            .map(|m| m.unwrap().as_str().to_string())
-            .collect::<Vec<_>>();
-        // capture group 0 is always entire match,
-        // group 1: $amount $unit, group 2: $amount_arabic_numerals
-        if result.len() != 3 {
-            return Err(ParseSentenceError.into());
-        }
-        let amount_unit = result.get(1).unwrap().split(' ').collect::<Vec<_>>();
-        // needs at least one numeral before unit
-        if amount_unit.len() < 2 {
-            return Err(ParseSentenceError.into());
+    if let Statement::Unit { words, .. } = parse_sentence(sentence).statement() {
+        if let Some((amount, unit)) = split_amount_and_unit_tokens(numeral_map, words) {
+            // needs at least one numeral before unit
+            if !amount.is_empty() {
+                return Ok(unit);
+            }
         }
-        let unit = amount_unit.last().unwrap();
-        return Ok(unit.to_string());
     }
     Err(ParseSentenceError.into())
 }
 
-/// Returns a Result with the amount extracted from a sentence with numerals as integer or ParseSententenceError if extraction failed.
-/// Sentences are expected to have an amount stated directly before the unit.
+/// Returns a Result with the amount extracted from a sentence with numerals or ParseSententenceError if extraction failed.
+/// Sentences are expected to have an amount stated directly before the unit,
+/// see split_amount_and_unit.
 /// # Example
 /// ```
 /// use std::collections::HashMap;
+/// use pangalacticcc::numerals::RomanNumerals;
+/// use pangalacticcc::rational::Rational;
 /// use pangalacticcc::textprocessing::extract_amounts_from_sentence;
-/// let mut nm:HashMap<String,char> = HashMap::new();
-/// nm.insert(String::from("glob"), 'I');
-/// nm.insert("prok".to_string(), 'V');
-/// assert_eq!(extract_amounts_from_sentence(&nm,"glob prok Iron is 782 Credits").unwrap(), 4);
-/// assert_eq!(extract_amounts_from_sentence(&nm,"glob glob Fish is 2 Credits").unwrap(),2);
-/// ```
-pub fn extract_amounts_from_sentence(
-    numeral_map: &HashMap<String, char>,
+/// let mut nm:HashMap<String, String> = HashMap::new();
+/// nm.insert(String::from("glob"), "I".to_string());
+/// nm.insert("prok".to_string(), "V".to_string());
+/// let roman = RomanNumerals::default();
+/// assert_eq!(extract_amounts_from_sentence(&roman, &nm,"glob prok Iron is 782 Credits").unwrap(), Rational::from(4));
+/// assert_eq!(extract_amounts_from_sentence(&roman, &nm,"glob glob Fish is 2 Credits").unwrap(), Rational::from(2));
+/// assert_eq!(extract_amounts_from_sentence(&roman, &nm,"glob glob Star Fish is 2 Credits").unwrap(), Rational::from(2));
+/// ```
+pub fn extract_amounts_from_sentence<N: NumeralSystem + ?Sized>(
+    system: &N,
+    numeral_map: &HashMap<String, String>,
     sentence: &str,
-) -> PccResult<i32> {
+) -> PccResult<Rational> {
     // assuming Credits is agreed upon
     // example input: glob prok Iron is 782 Credits
-    let unit_regex = Regex::new(r"^([\w ]+) is (\d+) Credits$").unwrap();
-    if let Some(captures) = unit_regex.captures(sentence.trim_start().trim_end()) {
-        let result = captures
-            .iter()
-            .map(|m| m.unwrap().as_str().to_string())
-            .collect::<Vec<_>>();
-        // capture group 0 is always entire match,
-        // group 1: $amount $unit, group 2: $amount_arabic_numerals
+    if let Statement::Unit { words, .. } = parse_sentence(sentence).statement() {
+        let amount = match split_amount_and_unit_tokens(numeral_map, words) {
+            Some((amount, _)) if !amount.is_empty() => amount,
+            _ => return Err(MapAlienNumeralError.into()), //could not find any alien numerals in map
+        };
+        return read_amount(system, numeral_map, amount);
+    }
+    Err(ParseSentenceError.into())
+}
 
-        if result.len() != 3 {
-            return Err(ParseSentenceError.into());
+/// Returns the value of an amount in a question written as a number in arabic numerals,
+/// e.g. "3" or "2.5" instead of alien numerals, or None if it is not.
+/// Fails with OverflowError if the number is too large to calculate with.
+/// A known alien numeral is never read as arabic number.
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use pangalacticcc::rational::{OverflowError, Rational};
+/// use pangalacticcc::textprocessing::arabic_amount;
+/// let mut nm: HashMap<String, String> = HashMap::new();
+/// nm.insert("glob".to_string(), "I".to_string());
+/// assert_eq!(arabic_amount(&nm, &["3"]), Some(Ok(Rational::from(3))));
+/// assert_eq!(arabic_amount(&nm, &["2.5"]), Some(Ok(Rational::new(5, 2))));
+/// assert_eq!(arabic_amount(&nm, &["1".repeat(40).as_str()]), Some(Err(OverflowError)));
+/// assert_eq!(arabic_amount(&nm, &["glob"]), None);
+/// assert_eq!(arabic_amount(&nm, &["3", "glob"]), None);
+/// ```
+pub fn arabic_amount(
+    numeral_map: &HashMap<String, String>,
+    amount: &[&str],
+) -> Option<Result<Rational, OverflowError>> {
+    match amount {
+        [word]
+            if get_ignore_case(numeral_map, word).is_none()
+                && word.starts_with(|c: char| c.is_ascii_digit()) =>
+        {
+            match word.parse::<Rational>() {
+                Ok(value) => Some(Ok(value)),
+                Err(ParseRationalError::Overflow) => Some(Err(OverflowError)),
+                Err(ParseRationalError::Invalid) => None,
+            }
         }
-        let mut amount = result.get(1).unwrap().split(' ').collect::<Vec<_>>();
-        amount.pop(); //discard last element which should be the unit
+        _ => None,
+    }
+}
 
-        let mapped = amount
-            .iter()
-            .filter_map(|x| numeral_map.get(*x))
-            .collect::<Vec<_>>();
-        // not all could be mapped -> return None
-        return if mapped.len() != amount.len() {
-            Err(MapAlienNumeralError.into()) //could not find all alien numerals in map
-        } else {
-            let mut result = String::new();
-            for n in mapped {
-                result.push(*n);
-            }
-            if let Ok(roman) = result.parse::<Roman>() {
-                return Ok(roman.get_value() as i32);
-            }
-            Err(ParseSentenceError.into())
-        };
+/// Returns the symbol of `system` a word of an amount in a question stands for,
+/// and true if the word is written as symbols of `system` directly, e.g. XLII,
+/// rather than as alien numeral. Known alien numerals come first.
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+/// use pangalacticcc::numerals::RomanNumerals;
+/// use pangalacticcc::textprocessing::amount_symbol;
+/// let mut nm: HashMap<String, String> = HashMap::new();
+/// nm.insert("glob".to_string(), "I".to_string());
+/// let roman = RomanNumerals::default();
+/// assert_eq!(amount_symbol(&roman, &nm, "GLOB"), Some(("I".to_string(), false)));
+/// assert_eq!(amount_symbol(&roman, &nm, "XLII"), Some(("XLII".to_string(), true)));
+/// assert_eq!(amount_symbol(&roman, &nm, "blub"), None);
+/// ```
+pub fn amount_symbol<N: NumeralSystem + ?Sized>(
+    system: &N,
+    numeral_map: &HashMap<String, String>,
+    word: &str,
+) -> Option<(String, bool)> {
+    match get_ignore_case(numeral_map, word) {
+        Some(symbol) => Some((symbol.clone(), false)),
+        None => system.parse_symbol(word).map(|symbol| (symbol, true)),
     }
-    Err(ParseSentenceError.into())
 }
+
+/// Returns the value of the alien numerals `amount` or ParseSentenceError if they can not be read
+fn read_amount<N: NumeralSystem + ?Sized>(
+    system: &N,
+    numeral_map: &HashMap<String, String>,
+    amount: &[Token],
+) -> PccResult<Rational> {
+    let mapped = amount
+        .iter()
+        .filter_map(|x| get_ignore_case(numeral_map, x.text()))
+        .map(|x| x.as_str())
+        .collect::<Vec<_>>();
+    match system.read(&mapped) {
+        Ok(reading) => Ok(reading.value()),
+        Err(_) => Err(ParseSentenceError.into()),
+    }
+}
+
 /// Returns a amount of Credits extracted from a sentence or None if extraction failed.
+/// Decimal amounts are kept exactly, the whole part may be grouped by commas.
 /// # Example
 /// ```
 /// use std::collections::HashMap;
+/// use pangalacticcc::rational::Rational;
 /// use pangalacticcc::textprocessing::{extract_amount_credits_from_sentence};
-/// assert_eq!(extract_amount_credits_from_sentence("glob prok Iron is 782 Credits"), Some(782));
-/// assert_eq!(extract_amount_credits_from_sentence("glob glob Fish is 2 Credits"), Some(2));
+/// assert_eq!(extract_amount_credits_from_sentence("glob prok Iron is 782 Credits"), Some(Rational::from(782)));
+/// assert_eq!(extract_amount_credits_from_sentence("glob glob Fish is 2 Credits"), Some(Rational::from(2)));
+/// assert_eq!(extract_amount_credits_from_sentence("glob Silver is 17.5 Credits"), Some(Rational::new(35, 2)));
+/// assert_eq!(extract_amount_credits_from_sentence("glob prok Gold is 57,800 Credits"), Some(Rational::from(57800)));
+/// assert_eq!(extract_amount_credits_from_sentence("glob glob Fish is 2 Zorkmids"), None);
 /// ```
-pub fn extract_amount_credits_from_sentence(sentence: &str) -> Option<i32> {
-    let unit_regex = Regex::new(r"^([\w ]+) is (\d+) Credits$").unwrap();
-    if let Some(captures) = unit_regex.captures(sentence) {
-        let result = captures
-            .iter()
-            .map(|m| m.unwrap().as_str().to_string())
-            .collect::<Vec<_>>();
-        // capture group 0 is always entire match,
-        // group 1: $amount $unit, group 2: $amount_arabic_numerals
-        if result.len() != 3 {
-            return None;
-        }
-        //println!("DEBUG: {:?}", result);
-        if let Some(num_credits) = result.get(2) {
-            if let Ok(result) = num_credits.parse::<i32>() {
-                return Some(result);
-            }
+pub fn extract_amount_credits_from_sentence(sentence: &str) -> Option<Rational> {
+    match parse_sentence(sentence).statement() {
+        Statement::Unit {
+            value, currency, ..
+        } if normalize_currency(currency.text()) == normalize_currency("Credits") => {
+            value.text().parse::<Rational>().ok()
         }
+        _ => None,
     }
-    None
 }
 
-/// Returns Result for credit conversion rate for unit extracted from a sentence with numerals as (unit: String, value: f64) tuple or an Error if extraction failed.
+/// Returns Result for credit conversion rate for unit extracted from a sentence with numerals as (unit: String, value: Rational) tuple or an Error if extraction failed.
+/// The value is kept as exact fraction, e.g. 10 Credits for 3 units yields 10/3 Credits per unit,
+/// decimal values like 17.5 Credits are exact as well.
 /// Sentences are expected to have an amount stated directly before the unit.
+/// Returns ZeroAmountError if the amount is zero, as the price can not be derived,
+/// and OverflowError if the value is too large to calculate with exactly.
 /// # Example
 /// ```
 /// use std::collections::HashMap;
+/// use pangalacticcc::numerals::RomanNumerals;
+/// use pangalacticcc::rational::Rational;
 /// use pangalacticcc::textprocessing::{extract_unit_values_from_sentence};
-/// let mut nm:HashMap<String,char> = HashMap::new();
-/// nm.insert(String::from("glob"), 'I');
-/// nm.insert("prok".to_string(), 'V');
+/// let mut nm:HashMap<String, String> = HashMap::new();
+/// nm.insert(String::from("glob"), "I".to_string());
+/// nm.insert("prok".to_string(), "V".to_string());
+/// let roman = RomanNumerals::default();
 /// assert_eq!(extract_unit_values_from_sentence(
-///     &nm,"glob prok Iron is 782 Credits").unwrap(),("Iron".to_string(),195.5)
+///     &roman, &nm,"glob prok Iron is 782 Credits").unwrap(),("Iron".to_string(), Rational::new(391, 2))
 /// );
 /// assert_eq!(extract_unit_values_from_sentence(
-///     &nm,"glob glob Fish is 2 Credits").unwrap(), ("Fish".to_string(),1.0)
+///     &roman, &nm,"glob glob glob Dirt is 10 Credits").unwrap(), ("Dirt".to_string(), Rational::new(10, 3))
 /// );
+/// nm.insert("nix".to_string(), "N".to_string());
+/// assert!(extract_unit_values_from_sentence(&roman, &nm,"nix Dirt is 0 Credits").is_err());
 /// ```
-pub fn extract_unit_values_from_sentence(
-    numeral_map: &HashMap<String, char>,
+pub fn extract_unit_values_from_sentence<N: NumeralSystem + ?Sized>(
+    system: &N,
+    numeral_map: &HashMap<String, String>,
     sentence: &str,
-) -> PccResult<(String, f64)> {
-    if let Ok(amount) = extract_amounts_from_sentence(numeral_map, sentence) {
-        if let Ok(unit) = extract_units_from_sentence(sentence) {
-            if let Some(num_credits) = extract_amount_credits_from_sentence(sentence) {
-                return Ok((unit, num_credits as f64 / amount as f64));
+) -> PccResult<(String, Rational)> {
+    extract_unit_value(system, numeral_map, parse_sentence(sentence).statement())
+}
+
+/// Returns Result for conversion rate for the unit of a parsed statement about units,
+/// see extract_unit_values_from_sentence. The value is given in the currency of the statement.
+pub fn extract_unit_value<N: NumeralSystem + ?Sized>(
+    system: &N,
+    numeral_map: &HashMap<String, String>,
+    statement: &Statement,
+) -> PccResult<(String, Rational)> {
+    if let Statement::Unit { words, value, .. } = statement {
+        if let Some((amount, unit)) = split_amount_and_unit_tokens(numeral_map, words) {
+            // needs at least one numeral before unit
+            if amount.is_empty() {
+                return Err(ParseSentenceError.into());
             }
+            let amount = read_amount(system, numeral_map, amount)?;
+            let num_credits = parse_value(value)?;
+            if amount == Rational::from(0) {
+                return Err(ZeroAmountError.into());
+            }
+            return Ok((unit, num_credits.checked_div(amount).ok_or(OverflowError)?));
+        }
+    }
+    Err(ParseSentenceError.into())
+}
+
+/// Returns the value of a number in arabic numerals of a statement,
+/// OverflowError if it is too large to calculate with exactly
+fn parse_value(token: &Token) -> PccResult<Rational> {
+    match token.text().parse::<Rational>() {
+        Ok(value) => Ok(value),
+        Err(ParseRationalError::Overflow) => Err(OverflowError.into()),
+        Err(ParseRationalError::Invalid) => Err(ParseSentenceError.into()),
+    }
+}
+
+/// Returns (currency, value, target) for a statement like "2 Zorkmids is 8 Credits",
+/// where one unit of `currency` is worth `value` units of `target`, or an Error if extraction failed.
+/// Returns ZeroAmountError if the amount is zero and ZeroExchangeRateError if the value is zero,
+/// OverflowError if the numbers are too large to calculate with exactly.
+/// # Example
+/// ```
+/// use pangalacticcc::parser::parse_sentence;
+/// use pangalacticcc::rational::Rational;
+/// use pangalacticcc::textprocessing::extract_exchange_rate;
+/// let rate = extract_exchange_rate(parse_sentence("2 Zorkmids is 8 Credits").statement()).unwrap();
+/// assert_eq!(rate, ("Zorkmids".to_string(), Rational::from(4), "Credits".to_string()));
+/// assert!(extract_exchange_rate(parse_sentence("1 Zorkmid is 0 Credits").statement()).is_err());
+/// assert!(extract_exchange_rate(parse_sentence("1 Credit is 1 Credits").statement()).is_err());
+/// ```
+pub fn extract_exchange_rate(statement: &Statement) -> PccResult<(String, Rational, String)> {
+    if let Statement::ExchangeRate {
+        amount,
+        currency,
+        value,
+        target,
+    } = statement
+    {
+        if normalize_currency(currency.text()) == normalize_currency(target.text()) {
+            return Err(ParseSentenceError.into());
+        }
+        let amount = parse_value(amount)?;
+        let value = parse_value(value)?;
+        if amount == Rational::from(0) {
+            return Err(ZeroAmountError.into());
+        }
+        if value == Rational::from(0) {
+            return Err(ZeroExchangeRateError.into());
         }
+        return Ok((
+            currency.text().to_string(),
+            value.checked_div(amount).ok_or(OverflowError)?,
+            target.text().to_string(),
+        ));
     }
     Err(ParseSentenceError.into())
 }
 
+/// Returns true if sentence chooses a numeral system, e.g. "numbers are roman"
+/// # Example
+/// ```
+/// use pangalacticcc::textprocessing::is_numeral_system_info;
+/// assert_eq!(is_numeral_system_info("numbers are roman"), true);
+/// assert_eq!(is_numeral_system_info("Numbers are Klingon"), true);
+/// assert_eq!(is_numeral_system_info("glob is I"), false);
+/// ```
+pub fn is_numeral_system_info(sentence: &str) -> bool {
+    extract_numeral_system_name(sentence).is_some()
+}
+
+/// Returns the name of the numeral system chosen in a sentence like "numbers are roman"
+/// or None if extraction failed.
+/// # Example
+/// ```
+/// use pangalacticcc::textprocessing::extract_numeral_system_name;
+/// assert_eq!(extract_numeral_system_name("numbers are roman"), Some("roman".to_string()));
+/// assert_eq!(extract_numeral_system_name("Numbers  are base\t7"), Some("base 7".to_string()));
+/// assert_eq!(extract_numeral_system_name("numbers are"), None);
+/// ```
+pub fn extract_numeral_system_name(sentence: &str) -> Option<String> {
+    match parse_sentence(sentence).statement() {
+        Statement::NumeralSystem { name } => {
+            Some(name.iter().map(|t| t.text()).collect::<Vec<_>>().join(" "))
+        }
+        _ => None,
+    }
+}
+
+/// Returns (alien_numeral, symbol) tuple from a sentence with numerals
+/// or None if extraction failed, e.g. because the symbol does not belong to `system`.
+/// The symbol is returned in the canonical form of the system.
+/// # Example
+/// ```
+/// use pangalacticcc::numerals::RomanNumerals;
+/// use pangalacticcc::textprocessing::{extract_numeral_mapping};
+/// let roman = RomanNumerals::default();
+/// assert_eq!(extract_numeral_mapping(&roman, "glob is I"), Some(("glob".to_string(), "I".to_string())));
+/// assert_eq!(extract_numeral_mapping(&roman, " Glob\tIS  I"), Some(("Glob".to_string(), "I".to_string())));
+/// assert_eq!(extract_numeral_mapping(&roman, "pish is A"), None);
+/// ```
+pub fn extract_numeral_mapping<N: NumeralSystem + ?Sized>(
+    system: &N,
+    sentence: &str,
+) -> Option<(String, String)> {
+    match parse_sentence(sentence).statement() {
+        Statement::Numeral { numeral, symbol } => Some((
+            numeral.text().to_string(),
+            system.parse_symbol(symbol.text())?,
+        )),
+        _ => None,
+    }
+}
+
 /// Returns (alien_numeral, roman_numeral) tuple from a sentence with numerals
 /// or None if extraction failed.
+/// The roman numeral may be a single symbol, a numeral of several symbols (e.g. IX)
+/// or a whole number in arabic numerals (e.g. 1000). Extended symbols for large values
+/// are accepted in vinculum (e.g. V̅) or apostrophus notation (e.g. IↃↃ or I)))
+/// and returned in vinculum notation.
 /// # Example
 /// ```
 /// use std::collections::HashMap;
 /// use pangalacticcc::textprocessing::{numerals_to_roman};
 /// assert_eq!(numerals_to_roman("glob is I"), Some(("glob".to_string(), "I".to_string())));
 /// assert_eq!(numerals_to_roman("pish is X"), Some(("pish".to_string(), "X".to_string())));
+/// assert_eq!(numerals_to_roman("zorg is CCI))"), Some(("zorg".to_string(), "X\u{305}".to_string())));
+/// assert_eq!(numerals_to_roman("pish is IX"), Some(("pish".to_string(), "IX".to_string())));
+/// assert_eq!(numerals_to_roman("zorg is 1000"), Some(("zorg".to_string(), "M".to_string())));
 /// assert_eq!(numerals_to_roman("pish is A"), None);
 /// ```
 pub fn numerals_to_roman(sentence: &str) -> Option<(String, String)> {
-    let numeral_regex = Regex::new(r"^(\w+) is ([IVXLCDM])$").unwrap();
-    if let Some(mapping) = numeral_regex.captures(sentence) {
-        let result = mapping
-            .iter()
-            .map(|m| m.unwrap().as_str().to_string())
-            .collect::<Vec<_>>();
-        // capture group 0 is always entire match,
-        // the other two should be found in a valid sentence
-        if result.len() != 3 {
-            return None;
-        }
-        return Some((
-            result.get(1).unwrap().clone(),
-            result.get(2).unwrap().clone(),
-        ));
-    }
-    None
+    extract_numeral_mapping(&RomanNumerals::default(), sentence)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::roman::Roman;
 
     const GLOB_I: &str = "glob is I";
     const PROK_V: &str = "prok is V";
@@ -277,12 +595,12 @@ mod tests {
         "how much wood could a woodchuck chuck if a woodchuck could chuck wood ?",
     ];
 
-    fn create_testmap() -> HashMap<String, char> {
+    fn create_testmap() -> HashMap<String, String> {
         let mut map = HashMap::new();
-        map.insert("tegj".to_string(), 'L');
-        map.insert("pish".to_string(), 'X');
-        map.insert("prok".to_string(), 'V');
-        map.insert("glob".to_string(), 'I');
+        map.insert("tegj".to_string(), "L".to_string());
+        map.insert("pish".to_string(), "X".to_string());
+        map.insert("prok".to_string(), "V".to_string());
+        map.insert("glob".to_string(), "I".to_string());
         map
     }
 
@@ -344,9 +662,13 @@ mod tests {
         );
     }
 
-    #[test] // no roman at end of sentence
-    fn test_numerals_to_roman_nonroman() {
-        assert_eq!(numerals_to_roman("sth is 5"), None);
+    #[test] // arabic number at end of sentence
+    fn test_numerals_to_roman_arabic_value() {
+        assert_eq!(
+            numerals_to_roman("sth is 5"),
+            Some(("sth".to_string(), "V".to_string()))
+        );
+        assert_eq!(numerals_to_roman("sth is 4000000"), None);
     }
 
     #[test] // no clear roman numeral at end
@@ -355,9 +677,29 @@ mod tests {
         assert_eq!(numerals_to_roman("sth is X5"), None);
     }
 
-    #[test] // todo: should this work?
+    #[test]
     fn test_numerals_to_roman_roman_multi() {
-        assert_eq!(numerals_to_roman("seven is VII"), None);
+        assert_eq!(
+            numerals_to_roman("seven is VII"),
+            Some(("seven".to_string(), "VII".to_string()))
+        );
+        assert_eq!(numerals_to_roman("seven is IIIIIII"), None);
+    }
+
+    #[test]
+    fn test_numerals_to_roman_extended() {
+        assert_eq!(
+            numerals_to_roman("grok is V\u{305}"),
+            Some(("grok".to_string(), "V\u{305}".to_string()))
+        );
+        assert_eq!(
+            numerals_to_roman("grok is I\u{2183}\u{2183}"),
+            Some(("grok".to_string(), "V\u{305}".to_string()))
+        );
+        assert_eq!(
+            numerals_to_roman("thousand is CI)"),
+            Some(("thousand".to_string(), "M".to_string()))
+        );
     }
 
     #[test]
@@ -457,7 +799,7 @@ mod tests {
     fn test_extract_unit_gold() {
         let gold_unit = "glob prok Gold is 57800 Credits";
         let expected = "Gold".to_string();
-        let result = extract_units_from_sentence(gold_unit);
+        let result = extract_units_from_sentence(&create_testmap(), gold_unit);
         assert_eq!(expected, result.unwrap())
     }
 
@@ -465,7 +807,7 @@ mod tests {
     fn test_extract_unit_iron() {
         let iron_unit = "pish pish Iron is 3910 Credits";
         let expected = "Iron".to_string();
-        let result = extract_units_from_sentence(iron_unit);
+        let result = extract_units_from_sentence(&create_testmap(), iron_unit);
         assert_eq!(expected, result.unwrap())
     }
 
@@ -473,14 +815,14 @@ mod tests {
     fn test_extract_unit_silver() {
         let silver_unit = "glob glob Silver is 34 Credits";
         let expected = "Silver".to_string();
-        let result = extract_units_from_sentence(silver_unit);
+        let result = extract_units_from_sentence(&create_testmap(), silver_unit);
         assert_eq!(expected, result.unwrap())
     }
 
     #[test]
     fn test_extract_unit_gold_err() {
         let expected = ParseSentenceError.to_string();
-        let result = extract_units_from_sentence(GLOB_I);
+        let result = extract_units_from_sentence(&create_testmap(), GLOB_I);
         assert_eq!(expected, result.unwrap_err().to_string())
     }
 
@@ -488,8 +830,9 @@ mod tests {
     fn test_extract_unit_val_gold() {
         let gold_unit = "glob prok Gold is 57800 Credits";
         let testmap = create_testmap();
-        let expected = ("Gold".to_string(), 57800.0 / 4.0);
-        let result = extract_unit_values_from_sentence(&testmap, gold_unit);
+        let expected = ("Gold".to_string(), Rational::new(57800, 4));
+        let result =
+            extract_unit_values_from_sentence(&RomanNumerals::default(), &testmap, gold_unit);
         assert_eq!(expected, result.unwrap())
     }
 
@@ -497,8 +840,9 @@ mod tests {
     fn test_extract_unit_val_iron() {
         let iron_unit = "pish pish Iron is 3910 Credits";
         let testmap = create_testmap();
-        let expected = ("Iron".to_string(), 3910.0 / 20.0);
-        let result = extract_unit_values_from_sentence(&testmap, iron_unit);
+        let expected = ("Iron".to_string(), Rational::new(3910, 20));
+        let result =
+            extract_unit_values_from_sentence(&RomanNumerals::default(), &testmap, iron_unit);
         assert_eq!(expected, result.unwrap())
     }
 
@@ -506,35 +850,37 @@ mod tests {
     fn test_extract_unit_values_silver() {
         let silver_unit = "glob glob Silver is 34 Credits";
         let testmap = create_testmap();
-        let expected = ("Silver".to_string(), 34.0 / 2.0);
-        let result = extract_unit_values_from_sentence(&testmap, silver_unit);
+        let expected = ("Silver".to_string(), Rational::new(34, 2));
+        let result =
+            extract_unit_values_from_sentence(&RomanNumerals::default(), &testmap, silver_unit);
         assert_eq!(expected, result.unwrap())
     }
 
     #[test]
     fn test_extract_amount_gold() {
         let gold_unit = "glob prok Gold is 57800 Credits";
-        let expected = 4;
+        let expected = Rational::from(4);
         let testmap = create_testmap();
-        let result = extract_amounts_from_sentence(&testmap, gold_unit);
+        let result = extract_amounts_from_sentence(&RomanNumerals::default(), &testmap, gold_unit);
         assert_eq!(expected, result.unwrap())
     }
     #[test]
 
     fn test_extract_amount_silver() {
         let silver_unit = "glob glob Silver is 34 Credits";
-        let expected = 2;
+        let expected = Rational::from(2);
         let testmap = create_testmap();
-        let result = extract_amounts_from_sentence(&testmap, silver_unit);
+        let result =
+            extract_amounts_from_sentence(&RomanNumerals::default(), &testmap, silver_unit);
         assert_eq!(expected, result.unwrap())
     }
 
     #[test]
     fn test_extract_amount_iron() {
         let iron_unit = "pish pish Iron is 3910 Credits";
-        let expected = 20;
+        let expected = Rational::from(20);
         let testmap = create_testmap();
-        let result = extract_amounts_from_sentence(&testmap, iron_unit);
+        let result = extract_amounts_from_sentence(&RomanNumerals::default(), &testmap, iron_unit);
         assert_eq!(expected, result.unwrap())
     }
 
@@ -542,8 +888,9 @@ mod tests {
     fn test_extract_unit_val_full_gold() {
         let gold_unit = "glob prok Gold is 57800 Credits";
         let testmap = create_testmap();
-        let expected = ("Gold".to_string(), 57800.0 / 4.0);
-        let result = extract_unit_values_from_sentence(&testmap, gold_unit);
+        let expected = ("Gold".to_string(), Rational::new(57800, 4));
+        let result =
+            extract_unit_values_from_sentence(&RomanNumerals::default(), &testmap, gold_unit);
         assert_eq!(expected, result.unwrap())
     }
 
@@ -551,17 +898,58 @@ mod tests {
     fn test_extract_unit_val_full_silver() {
         let silver_unit = "glob glob Silver is 34 Credits";
         let testmap = create_testmap();
-        let expected = ("Silver".to_string(), 34.0 / 2.0);
-        let result = extract_unit_values_from_sentence(&testmap, silver_unit);
+        let expected = ("Silver".to_string(), Rational::new(34, 2));
+        let result =
+            extract_unit_values_from_sentence(&RomanNumerals::default(), &testmap, silver_unit);
         assert_eq!(expected, result.unwrap())
     }
 
     #[test]
     fn test_extract_unit_val_full_iron() {
         let iron_unit = "pish pish Iron is 3910 Credits";
-        let expected = ("Iron".to_string(), 3910.0 / 20.0);
+        let expected = ("Iron".to_string(), Rational::new(3910, 20));
         let testmap = create_testmap();
-        let result = extract_unit_values_from_sentence(&testmap, iron_unit);
+        let result =
+            extract_unit_values_from_sentence(&RomanNumerals::default(), &testmap, iron_unit);
         assert_eq!(expected, result.unwrap())
     }
+
+    #[test]
+    fn test_extract_unit_multi_word() {
+        let testmap = create_testmap();
+        let result = extract_unit_values_from_sentence(
+            &RomanNumerals::default(),
+            &testmap,
+            "glob glob Dark Matter is 34 Credits",
+        );
+        assert_eq!(
+            result.unwrap(),
+            ("Dark Matter".to_string(), Rational::from(17))
+        );
+    }
+
+    #[test]
+    fn test_extract_unit_quoted() {
+        let testmap = create_testmap();
+        let sentence = "pish \"glob Dust\" is 30 Credits";
+        assert!(is_unit_info(sentence));
+        let result =
+            extract_unit_values_from_sentence(&RomanNumerals::default(), &testmap, sentence);
+        assert_eq!(
+            result.unwrap(),
+            ("glob Dust".to_string(), Rational::from(3))
+        );
+    }
+
+    #[test]
+    fn test_split_amount_and_unit_malformed_quotes() {
+        let testmap = create_testmap();
+        assert_eq!(split_amount_and_unit(&testmap, "glob \"Dust"), None);
+        assert_eq!(split_amount_and_unit(&testmap, "glob \"Dust\" Iron"), None);
+        assert_eq!(split_amount_and_unit(&testmap, "glob \"\""), None);
+        assert_eq!(
+            split_amount_and_unit(&testmap, "Dust"),
+            Some((vec![], "Dust".to_string()))
+        );
+    }
 }
@@ -1,4 +1,5 @@
 use assert_cmd::Command;
+use pangalacticcc::MAX_WAITING;
 use predicates::prelude::*;
 use rand::{distributions::Alphanumeric, Rng};
 use std::error::Error;
@@ -16,6 +17,53 @@ const INPUT2: &str = "tests/input/input2.txt";
 const OUTPUT2: &str = "tests/expected/input2.txt.out";
 const INPUT3_UNIT_REDEF: &str = "tests/input/input3_confusing_unit.txt";
 const OUTPUT3_UNIT_REDEF: &str = "tests/expected/input3_confusing_unit.txt.out";
+const INPUT4_EXACT_PRICES: &str = "tests/input/input4_exact_prices.txt";
+const OUTPUT4_EXACT_PRICES: &str = "tests/expected/input4_exact_prices.txt.out";
+const INPUT5_LENIENT: &str = "tests/input/input5_lenient.txt";
+const OUTPUT5_LENIENT: &str = "tests/expected/input5_lenient.txt.out";
+const OUTPUT5_STRICT: &str = "tests/expected/input5_strict.txt.out";
+const INPUT6_NUMERAL_SYSTEM: &str = "tests/input/input6_numeral_system.txt";
+const OUTPUT6_NUMERAL_SYSTEM: &str = "tests/expected/input6_numeral_system.txt.out";
+const INPUT7_BASE7: &str = "tests/input/input7_base7.txt";
+const OUTPUT7_BASE7: &str = "tests/expected/input7_base7.txt.out";
+const INPUT8_GLYPHS: &str = "tests/input/input8_glyphs.txt";
+const OUTPUT8_GLYPHS: &str = "tests/expected/input8_glyphs.txt.out";
+const INPUT9_FRACTIONS: &str = "tests/input/input9_fractions.txt";
+const OUTPUT9_FRACTIONS: &str = "tests/expected/input9_fractions.txt.out";
+const INPUT10_ZERO: &str = "tests/input/input10_zero.txt";
+const OUTPUT10_ZERO: &str = "tests/expected/input10_zero.txt.out";
+const INPUT11_COMPOUND: &str = "tests/input/input11_compound.txt";
+const OUTPUT11_COMPOUND: &str = "tests/expected/input11_compound.txt.out";
+const INPUT12_MULTIWORD_UNITS: &str = "tests/input/input12_multiword_units.txt";
+const OUTPUT12_MULTIWORD_UNITS: &str = "tests/expected/input12_multiword_units.txt.out";
+const INPUT13_WHITESPACE_CASE: &str = "tests/input/input13_whitespace_case.txt";
+const OUTPUT13_WHITESPACE_CASE: &str = "tests/expected/input13_whitespace_case.txt.out";
+const INPUT14_STREAM: &str = "tests/input/input14_stream.txt";
+const OUTPUT14_STREAM: &str = "tests/expected/input14_stream.txt.out";
+const INPUT15_CURRENCIES: &str = "tests/input/input15_currencies.txt";
+const OUTPUT15_CURRENCIES: &str = "tests/expected/input15_currencies.txt.out";
+const INPUT16_UNIT_CONVERSION: &str = "tests/input/input16_unit_conversion.txt";
+const OUTPUT16_UNIT_CONVERSION: &str = "tests/expected/input16_unit_conversion.txt.out";
+const INPUT17_HOW_TO_SAY: &str = "tests/input/input17_how_to_say.txt";
+const OUTPUT17_HOW_TO_SAY: &str = "tests/expected/input17_how_to_say.txt.out";
+const INPUT18_PURCHASE: &str = "tests/input/input18_purchase.txt";
+const OUTPUT18_PURCHASE: &str = "tests/expected/input18_purchase.txt.out";
+const INPUT19_DECIMAL_CREDITS: &str = "tests/input/input19_decimal_credits.txt";
+const OUTPUT19_DECIMAL_CREDITS: &str = "tests/expected/input19_decimal_credits.txt.out";
+const INPUT20_LITERAL_AMOUNTS: &str = "tests/input/input20_literal_amounts.txt";
+const OUTPUT20_LITERAL_AMOUNTS: &str = "tests/expected/input20_literal_amounts.txt.out";
+const INPUT21_LARGE_AMOUNTS: &str = "tests/input/input21_large_amounts.txt";
+const OUTPUT21_LARGE_AMOUNTS: &str = "tests/expected/input21_large_amounts.txt.out";
+const INPUT22_COMMENTS: &str = "tests/input/input22_comments.txt";
+const OUTPUT22_COMMENTS: &str = "tests/expected/input22_comments.txt.out";
+const INPUT23_INCLUDE: &str = "tests/input/input23_include.txt";
+const OUTPUT23_INCLUDE: &str = "tests/expected/input23_include.txt.out";
+const INPUT23_INCLUDE_STDIN: &str = "tests/input/input23_include_stdin.txt";
+const OUTPUT23_INCLUDE_STDIN: &str = "tests/expected/input23_include_stdin.txt.out";
+const INPUT24_COMPOUND_HOW_TO_SAY: &str = "tests/input/input24_compound_how_to_say.txt";
+const OUTPUT24_COMPOUND_HOW_TO_SAY: &str = "tests/expected/input24_compound_how_to_say.txt.out";
+const INPUT25_LARGE_ARABIC_AMOUNTS: &str = "tests/input/input25_large_arabic_amounts.txt";
+const OUTPUT25_LARGE_ARABIC_AMOUNTS: &str = "tests/expected/input25_large_arabic_amounts.txt.out";
 // test boilerplate
 fn gen_non_existing_file() -> String {
     loop {
@@ -57,7 +105,7 @@ fn run_stdin(input_file: &str, args: &[&str], expected_file: &str) -> TestResult
 #[test]
 fn die_on_non_existing_file() -> TestResult {
     let non_existing = gen_non_existing_file();
-    let expected = format!(".* [(]os error 2[)]");
+    let expected = ".* [(]os error 2[)]".to_string();
     Command::cargo_bin(PRG)?
         .arg(&non_existing)
         .assert()
@@ -90,3 +138,221 @@ fn test_input2() -> TestResult {
 fn test_input3_confusing_unit() -> TestResult {
     run(&[INPUT3_UNIT_REDEF], OUTPUT3_UNIT_REDEF)
 }
+
+#[test]
+fn test_input4_exact_prices() -> TestResult {
+    run(&[INPUT4_EXACT_PRICES], OUTPUT4_EXACT_PRICES)
+}
+
+#[test]
+fn test_input5_lenient() -> TestResult {
+    run(&["--lenient", INPUT5_LENIENT], OUTPUT5_LENIENT)
+}
+
+#[test]
+fn test_input5_lenient_stdin() -> TestResult {
+    run_stdin(INPUT5_LENIENT, &["-l"], OUTPUT5_LENIENT)
+}
+
+#[test]
+fn test_input5_strict() -> TestResult {
+    run(&[INPUT5_LENIENT], OUTPUT5_STRICT)
+}
+
+#[test]
+fn test_input6_numeral_system() -> TestResult {
+    run(&[INPUT6_NUMERAL_SYSTEM], OUTPUT6_NUMERAL_SYSTEM)
+}
+
+#[test]
+fn test_input7_base7() -> TestResult {
+    run(&[INPUT7_BASE7], OUTPUT7_BASE7)
+}
+
+#[test]
+fn test_input8_glyphs() -> TestResult {
+    run(
+        &["-l", "--style", "unicode-lowercase", INPUT8_GLYPHS],
+        OUTPUT8_GLYPHS,
+    )
+}
+
+#[test]
+fn test_input9_fractions() -> TestResult {
+    run(&[INPUT9_FRACTIONS], OUTPUT9_FRACTIONS)
+}
+
+#[test]
+fn test_input10_zero() -> TestResult {
+    run(&[INPUT10_ZERO], OUTPUT10_ZERO)
+}
+
+#[test]
+fn test_input11_compound() -> TestResult {
+    run(&[INPUT11_COMPOUND], OUTPUT11_COMPOUND)
+}
+
+#[test]
+fn test_input12_multiword_units() -> TestResult {
+    run(&[INPUT12_MULTIWORD_UNITS], OUTPUT12_MULTIWORD_UNITS)
+}
+
+#[test]
+fn test_input13_whitespace_case() -> TestResult {
+    run(&[INPUT13_WHITESPACE_CASE], OUTPUT13_WHITESPACE_CASE)
+}
+
+#[test]
+fn test_input14_stream() -> TestResult {
+    run(&["--stream", INPUT14_STREAM], OUTPUT14_STREAM)
+}
+
+#[test]
+fn test_input14_stream_stdin() -> TestResult {
+    run_stdin(INPUT14_STREAM, &["--stream"], OUTPUT14_STREAM)
+}
+
+#[test]
+fn test_stream_waiting_questions_are_limited() -> TestResult {
+    // one question more than can be kept waiting, the first one is answered right away
+    let question = "how much is glob ?\n";
+    let input = question.repeat(MAX_WAITING + 1) + "glob is I\n";
+    let output = Command::cargo_bin(PRG)?
+        .arg("--stream")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output)?;
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), MAX_WAITING + 1);
+    assert_ne!(lines[0], "glob is 1");
+    assert!(lines[1..].iter().all(|line| *line == "glob is 1"));
+    Ok(())
+}
+
+#[test]
+fn test_input15_currencies() -> TestResult {
+    run(&[INPUT15_CURRENCIES], OUTPUT15_CURRENCIES)
+}
+
+#[test]
+fn test_input16_unit_conversion() -> TestResult {
+    run(&[INPUT16_UNIT_CONVERSION], OUTPUT16_UNIT_CONVERSION)
+}
+
+#[test]
+fn test_input17_how_to_say() -> TestResult {
+    run(&[INPUT17_HOW_TO_SAY], OUTPUT17_HOW_TO_SAY)
+}
+
+#[test]
+fn test_input17_how_to_say_stream() -> TestResult {
+    run(&["--stream", INPUT17_HOW_TO_SAY], OUTPUT17_HOW_TO_SAY)
+}
+
+#[test]
+fn test_input18_purchase() -> TestResult {
+    run(&[INPUT18_PURCHASE], OUTPUT18_PURCHASE)
+}
+
+#[test]
+fn test_input19_decimal_credits() -> TestResult {
+    run(&[INPUT19_DECIMAL_CREDITS], OUTPUT19_DECIMAL_CREDITS)
+}
+
+#[test]
+fn test_input20_literal_amounts() -> TestResult {
+    run(&[INPUT20_LITERAL_AMOUNTS], OUTPUT20_LITERAL_AMOUNTS)
+}
+
+#[test]
+fn test_input20_literal_amounts_stream() -> TestResult {
+    run(
+        &["--stream", INPUT20_LITERAL_AMOUNTS],
+        OUTPUT20_LITERAL_AMOUNTS,
+    )
+}
+
+#[test]
+fn test_input21_large_amounts() -> TestResult {
+    run(&[INPUT21_LARGE_AMOUNTS], OUTPUT21_LARGE_AMOUNTS)
+}
+
+#[test]
+fn test_input22_comments() -> TestResult {
+    run(&[INPUT22_COMMENTS], OUTPUT22_COMMENTS)
+}
+
+#[test]
+fn test_input22_comments_stream() -> TestResult {
+    run(&["--stream", INPUT22_COMMENTS], OUTPUT22_COMMENTS)
+}
+
+#[test]
+fn test_input23_include() -> TestResult {
+    run(&[INPUT23_INCLUDE], OUTPUT23_INCLUDE)
+}
+
+#[test]
+fn test_input23_include_stream() -> TestResult {
+    run(&["--stream", INPUT23_INCLUDE], OUTPUT23_INCLUDE)
+}
+
+#[test]
+fn test_input23_include_stdin() -> TestResult {
+    run_stdin(INPUT23_INCLUDE_STDIN, &[], OUTPUT23_INCLUDE_STDIN)
+}
+
+#[test]
+fn test_input24_compound_how_to_say() -> TestResult {
+    run(&[INPUT24_COMPOUND_HOW_TO_SAY], OUTPUT24_COMPOUND_HOW_TO_SAY)
+}
+
+#[test]
+fn test_input25_large_arabic_amounts() -> TestResult {
+    run(
+        &[INPUT25_LARGE_ARABIC_AMOUNTS],
+        OUTPUT25_LARGE_ARABIC_AMOUNTS,
+    )
+}
+
+#[test]
+fn test_input25_large_arabic_amounts_stream() -> TestResult {
+    run(
+        &["--stream", INPUT25_LARGE_ARABIC_AMOUNTS],
+        OUTPUT25_LARGE_ARABIC_AMOUNTS,
+    )
+}
+
+#[test]
+fn test_include_non_existing_file() -> TestResult {
+    let non_existing = gen_non_existing_file();
+    Command::cargo_bin(PRG)?
+        .write_stdin(format!("include \"{}\"\n", non_existing))
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(format!(
+            "Can not include {}: ",
+            non_existing
+        )))
+        .stdout(predicate::str::contains("included from stdin line 1"));
+    Ok(())
+}
+
+#[test]
+fn test_input1_stream() -> TestResult {
+    run(&["--stream", INPUT1], OUTPUT1)
+}
+
+#[test]
+fn test_unknown_style() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--style", "klingon", INPUT1])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("klingon"));
+    Ok(())
+}